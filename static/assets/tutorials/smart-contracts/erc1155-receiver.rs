@@ -0,0 +1,68 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::receiver::{Receiver, ReceiverRef};
+
+/// A contract that accepts or rejects the tokens an `Erc1155` sends to it.
+///
+/// It implements the `Erc1155TokenReceiver` trait of `erc1155.rs`, so that
+/// contract must be added as a dependency with the `ink-as-dependency`
+/// feature enabled.
+#[ink::contract]
+mod receiver {
+    use erc1155::{Erc1155TokenReceiver, TokenId, ON_RECEIVED_SELECTOR};
+    use ink::prelude::vec::Vec;
+
+    #[ink(storage)]
+    pub struct Receiver {
+        /// Whether `on_received` accepts the tokens.
+        accept: bool,
+    }
+
+    impl Receiver {
+        /// Create a new receiver that accepts tokens if `accept` is `true`.
+        #[ink(constructor)]
+        pub fn new(accept: bool) -> Self {
+            Self { accept }
+        }
+    }
+
+    impl Erc1155TokenReceiver for Receiver {
+        #[ink(message)]
+        fn on_received(
+            &mut self,
+            _operator: AccountId,
+            _from: AccountId,
+            _token_ids: Vec<TokenId>,
+            _values: Vec<Balance>,
+            _data: Vec<u8>,
+        ) -> [u8; 4] {
+            if self.accept {
+                ON_RECEIVED_SELECTOR
+            } else {
+                [0; 4]
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn on_received(receiver: &mut Receiver) -> [u8; 4] {
+            let accounts = ink::env::test::default_accounts::<Environment>();
+            receiver.on_received(
+                accounts.alice,
+                accounts.alice,
+                Vec::from([1]),
+                Vec::from([10]),
+                Vec::new(),
+            )
+        }
+
+        #[ink::test]
+        fn on_received_works() {
+            assert_eq!(on_received(&mut Receiver::new(true)), ON_RECEIVED_SELECTOR);
+            assert_eq!(on_received(&mut Receiver::new(false)), [0; 4]);
+        }
+    }
+}
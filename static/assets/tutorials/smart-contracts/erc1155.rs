@@ -0,0 +1,1019 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink::prelude::vec::Vec;
+use ink::primitives::AccountId;
+
+/// The balance type used by the multi-token contract.
+pub type Balance = u128;
+
+/// The identifier of a token type.
+pub type TokenId = u128;
+
+/// The response a receiver returns from `on_received` to accept tokens.
+pub const ON_RECEIVED_SELECTOR: [u8; 4] = [0xF2, 0x3A, 0x6E, 0x61];
+
+/// The interface a contract implements to accept tokens from an `Erc1155`.
+#[ink::trait_definition]
+pub trait Erc1155TokenReceiver {
+    /// Called after `values` of `token_ids` were transferred to this contract.
+    ///
+    /// Must return `ON_RECEIVED_SELECTOR` to accept the tokens. Any other
+    /// response, or a failing call, reverts the transfer.
+    #[ink(message, selector = 0xF23A6E61)]
+    fn on_received(
+        &mut self,
+        operator: AccountId,
+        from: AccountId,
+        token_ids: Vec<TokenId>,
+        values: Vec<Balance>,
+        data: Vec<u8>,
+    ) -> [u8; 4];
+}
+
+/// A multi-token contract following the ERC-1155 standard.
+///
+/// A single contract holds any number of token types. A token type minted
+/// with a supply of one behaves like a non-fungible token, while larger
+/// supplies behave like fungible tokens.
+#[ink::contract]
+mod erc1155 {
+    use crate::TokenId;
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+
+    /// Create storage for a simple ERC-1155 contract.
+    #[ink(storage)]
+    pub struct Erc1155 {
+        /// Mapping from owner and token type to the owned balance.
+        balances: Mapping<(AccountId, TokenId), Balance>,
+        /// Mapping from owner to the operators they approved for all tokens.
+        approvals: Mapping<(AccountId, AccountId), ()>,
+        /// The account allowed to mint tokens.
+        owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct TransferSingle {
+        #[ink(topic)]
+        operator: AccountId,
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        token_id: TokenId,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct TransferBatch {
+        #[ink(topic)]
+        operator: AccountId,
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        token_ids: Vec<TokenId>,
+        values: Vec<Balance>,
+    }
+
+    #[ink(event)]
+    pub struct ApprovalForAll {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        approved: bool,
+    }
+
+    /// Specify ERC-1155 error type.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not the contract owner.
+        NotOwner,
+        /// Returned if the caller is neither the owner of the tokens nor an
+        /// approved operator.
+        NotApproved,
+        /// Returned if not enough balance to fulfill a request is available.
+        InsufficientBalance,
+        /// Returned if a balance does not fit in a `Balance`.
+        Overflow,
+        /// Returned if the caller tries to approve itself as an operator.
+        SelfApproval,
+        /// Returned if `token_ids` and `values` have different lengths.
+        BatchLengthMismatch,
+        /// Returned if a batch holds more than `MAX_BATCH_LEN` transfers.
+        BatchTooLarge,
+        /// Returned if the receiving contract did not accept the tokens.
+        TransferRejected,
+    }
+
+    /// Specify the ERC-1155 result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// The maximum number of transfers accepted by `safe_batch_transfer_from`.
+    pub const MAX_BATCH_LEN: usize = 100;
+
+    impl Erc1155 {
+        /// Create a new ERC-1155 contract without any tokens.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                balances: Mapping::default(),
+                approvals: Mapping::default(),
+                owner: Self::env().caller(),
+            }
+        }
+
+        /// Returns the balance of `token_id` held by `owner`.
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId, token_id: TokenId) -> Balance {
+            self.balances.get((owner, token_id)).unwrap_or_default()
+        }
+
+        /// Returns the balance of each `(owner, token_id)` pair.
+        #[ink(message)]
+        pub fn balance_of_batch(&self, pairs: Vec<(AccountId, TokenId)>) -> Vec<Balance> {
+            pairs
+                .into_iter()
+                .map(|(owner, token_id)| self.balance_of(owner, token_id))
+                .collect()
+        }
+
+        /// Returns `true` if `operator` may transfer all tokens of `owner`.
+        #[ink(message)]
+        pub fn is_approved_for_all(&self, owner: AccountId, operator: AccountId) -> bool {
+            self.approvals.contains((owner, operator))
+        }
+
+        /// Allows or disallows `operator` to transfer all tokens of the caller.
+        #[ink(message)]
+        pub fn set_approval_for_all(&mut self, operator: AccountId, approved: bool) -> Result<()> {
+            let owner = self.env().caller();
+            if operator == owner {
+                return Err(Error::SelfApproval);
+            }
+
+            if approved {
+                self.approvals.insert((owner, operator), &());
+            } else {
+                self.approvals.remove((owner, operator));
+            }
+
+            self.env().emit_event(ApprovalForAll {
+                owner,
+                operator,
+                approved,
+            });
+
+            Ok(())
+        }
+
+        /// Creates `value` tokens of type `token_id` and gives them to `to`.
+        ///
+        /// Only the contract owner can mint tokens.
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, token_id: TokenId, value: Balance) -> Result<()> {
+            let operator = self.env().caller();
+            if operator != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            let balance = self
+                .balance_of(to, token_id)
+                .checked_add(value)
+                .ok_or(Error::Overflow)?;
+            self.set_balance(to, token_id, balance);
+
+            self.env().emit_event(TransferSingle {
+                operator,
+                from: None,
+                to: Some(to),
+                token_id,
+                value,
+            });
+
+            Ok(())
+        }
+
+        /// Transfers `value` tokens of type `token_id` from `from` to `to`.
+        ///
+        /// The caller must be `from` or one of its operators. If `to` is a
+        /// contract, its `on_received` hook must accept the tokens.
+        #[ink(message)]
+        pub fn safe_transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            token_id: TokenId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<()> {
+            let operator = self.ensure_approved(from)?;
+            self.transfer_from_to(from, to, token_id, value)?;
+
+            self.env().emit_event(TransferSingle {
+                operator,
+                from: Some(from),
+                to: Some(to),
+                token_id,
+                value,
+            });
+
+            self.call_on_received(
+                operator,
+                from,
+                to,
+                Vec::from([token_id]),
+                Vec::from([value]),
+                data,
+            )
+        }
+
+        /// Transfers `values[i]` tokens of type `token_ids[i]` from `from` to
+        /// `to` for every `i`.
+        ///
+        /// Either every transfer succeeds or none of them is applied. A token
+        /// type may appear several times in one batch.
+        #[ink(message)]
+        pub fn safe_batch_transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            token_ids: Vec<TokenId>,
+            values: Vec<Balance>,
+            data: Vec<u8>,
+        ) -> Result<()> {
+            let operator = self.ensure_approved(from)?;
+            if token_ids.len() != values.len() {
+                return Err(Error::BatchLengthMismatch);
+            }
+            if token_ids.len() > MAX_BATCH_LEN {
+                return Err(Error::BatchTooLarge);
+            }
+
+            // Check the whole batch up front so that no transfer is applied
+            // when `from` cannot cover all of them.
+            let totals = batch_totals(&token_ids, &values)?;
+            for (token_id, total) in &totals {
+                if self.balance_of(from, *token_id) < *total {
+                    return Err(Error::InsufficientBalance);
+                }
+                if from != to {
+                    self.balance_of(to, *token_id)
+                        .checked_add(*total)
+                        .ok_or(Error::Overflow)?;
+                }
+            }
+
+            for (token_id, total) in totals {
+                self.transfer_from_to(from, to, token_id, total)?;
+            }
+
+            self.env().emit_event(TransferBatch {
+                operator,
+                from: Some(from),
+                to: Some(to),
+                token_ids: token_ids.clone(),
+                values: values.clone(),
+            });
+
+            self.call_on_received(operator, from, to, token_ids, values, data)
+        }
+
+        /// Returns the caller if it may move the tokens of `from`.
+        fn ensure_approved(&self, from: AccountId) -> Result<AccountId> {
+            let caller = self.env().caller();
+            if caller != from && !self.is_approved_for_all(from, caller) {
+                return Err(Error::NotApproved);
+            }
+            Ok(caller)
+        }
+
+        fn transfer_from_to(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            token_id: TokenId,
+            value: Balance,
+        ) -> Result<()> {
+            let from_balance = self.balance_of(from, token_id);
+            let new_from_balance = from_balance
+                .checked_sub(value)
+                .ok_or(Error::InsufficientBalance)?;
+
+            // A transfer to oneself leaves the balance as it was.
+            if from == to {
+                return Ok(());
+            }
+            let new_to_balance = self
+                .balance_of(to, token_id)
+                .checked_add(value)
+                .ok_or(Error::Overflow)?;
+
+            self.set_balance(from, token_id, new_from_balance);
+            self.set_balance(to, token_id, new_to_balance);
+
+            Ok(())
+        }
+
+        /// Stores the balance of `owner`, removing it when it drops to zero.
+        fn set_balance(&mut self, owner: AccountId, token_id: TokenId, value: Balance) {
+            if value == 0 {
+                self.balances.remove((owner, token_id));
+            } else {
+                self.balances.insert((owner, token_id), &value);
+            }
+        }
+
+        /// Calls the `on_received` hook of `to` and fails unless it accepts the
+        /// tokens. Accounts without code always accept tokens.
+        fn call_on_received(
+            &self,
+            operator: AccountId,
+            from: AccountId,
+            to: AccountId,
+            token_ids: Vec<TokenId>,
+            values: Vec<Balance>,
+            data: Vec<u8>,
+        ) -> Result<()> {
+            use crate::ON_RECEIVED_SELECTOR;
+            use ink::env::call::{build_call, ExecutionInput, Selector};
+
+            if !self.env().is_contract(&to) {
+                return Ok(());
+            }
+
+            let result = build_call::<Environment>()
+                .call(to)
+                .gas_limit(0)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ON_RECEIVED_SELECTOR))
+                        .push_arg(operator)
+                        .push_arg(from)
+                        .push_arg(token_ids)
+                        .push_arg(values)
+                        .push_arg(data),
+                )
+                .returns::<[u8; 4]>()
+                .try_invoke();
+            on_received_outcome(result)
+        }
+    }
+
+    /// Accepts a transfer only if the receiver returned `ON_RECEIVED_SELECTOR`.
+    ///
+    /// A receiver that answers with anything else, does not implement the
+    /// hook, or fails, rejects the tokens.
+    fn on_received_outcome(result: ink::env::Result<ink::MessageResult<[u8; 4]>>) -> Result<()> {
+        match result {
+            Ok(Ok(response)) if response == crate::ON_RECEIVED_SELECTOR => Ok(()),
+            _ => Err(Error::TransferRejected),
+        }
+    }
+
+    /// Sums the values of each distinct token type in a batch.
+    fn batch_totals(token_ids: &[TokenId], values: &[Balance]) -> Result<Vec<(TokenId, Balance)>> {
+        let mut totals: Vec<(TokenId, Balance)> = Vec::new();
+        for (token_id, value) in token_ids.iter().zip(values) {
+            match totals.iter_mut().find(|(id, _)| id == token_id) {
+                Some((_, total)) => *total = total.checked_add(*value).ok_or(Error::Overflow)?,
+                None => totals.push((*token_id, *value)),
+            }
+        }
+        Ok(totals)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        type Event = <Erc1155 as ink::reflect::ContractEventBase>::Type;
+
+        fn default_accounts() -> ink::env::test::DefaultAccounts<Environment> {
+            ink::env::test::default_accounts::<Environment>()
+        }
+
+        fn alice() -> AccountId {
+            default_accounts().alice
+        }
+
+        fn bob() -> AccountId {
+            default_accounts().bob
+        }
+
+        fn charlie() -> AccountId {
+            default_accounts().charlie
+        }
+
+        fn set_caller(caller: AccountId) {
+            ink::env::test::set_caller::<Environment>(caller);
+        }
+
+        fn recorded_events() -> Vec<Event> {
+            ink::env::test::recorded_events()
+                .map(|event| {
+                    <Event as scale::Decode>::decode(&mut &event.data[..])
+                        .expect("encountered invalid contract event data buffer")
+                })
+                .collect()
+        }
+
+        fn assert_transfer_single_event(
+            event: &Event,
+            expected_operator: AccountId,
+            expected_from: Option<AccountId>,
+            expected_to: Option<AccountId>,
+            expected_token_id: TokenId,
+            expected_value: Balance,
+        ) {
+            if let Event::TransferSingle(TransferSingle {
+                operator,
+                from,
+                to,
+                token_id,
+                value,
+            }) = event
+            {
+                assert_eq!(
+                    *operator, expected_operator,
+                    "encountered invalid TransferSingle.operator"
+                );
+                assert_eq!(
+                    *from, expected_from,
+                    "encountered invalid TransferSingle.from"
+                );
+                assert_eq!(*to, expected_to, "encountered invalid TransferSingle.to");
+                assert_eq!(
+                    *token_id, expected_token_id,
+                    "encountered invalid TransferSingle.token_id"
+                );
+                assert_eq!(
+                    *value, expected_value,
+                    "encountered invalid TransferSingle.value"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected a TransferSingle event")
+            }
+        }
+
+        fn assert_transfer_batch_event(
+            event: &Event,
+            expected_operator: AccountId,
+            expected_from: Option<AccountId>,
+            expected_to: Option<AccountId>,
+            expected_token_ids: &[TokenId],
+            expected_values: &[Balance],
+        ) {
+            if let Event::TransferBatch(TransferBatch {
+                operator,
+                from,
+                to,
+                token_ids,
+                values,
+            }) = event
+            {
+                assert_eq!(
+                    *operator, expected_operator,
+                    "encountered invalid TransferBatch.operator"
+                );
+                assert_eq!(
+                    *from, expected_from,
+                    "encountered invalid TransferBatch.from"
+                );
+                assert_eq!(*to, expected_to, "encountered invalid TransferBatch.to");
+                assert_eq!(
+                    token_ids, expected_token_ids,
+                    "encountered invalid TransferBatch.token_ids"
+                );
+                assert_eq!(
+                    values, expected_values,
+                    "encountered invalid TransferBatch.values"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected a TransferBatch event")
+            }
+        }
+
+        fn assert_approval_for_all_event(
+            event: &Event,
+            expected_owner: AccountId,
+            expected_operator: AccountId,
+            expected_approved: bool,
+        ) {
+            if let Event::ApprovalForAll(ApprovalForAll {
+                owner,
+                operator,
+                approved,
+            }) = event
+            {
+                assert_eq!(
+                    *owner, expected_owner,
+                    "encountered invalid ApprovalForAll.owner"
+                );
+                assert_eq!(
+                    *operator, expected_operator,
+                    "encountered invalid ApprovalForAll.operator"
+                );
+                assert_eq!(
+                    *approved, expected_approved,
+                    "encountered invalid ApprovalForAll.approved"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected an ApprovalForAll event")
+            }
+        }
+
+        /// Creates a contract in which `alice` owns 100 tokens of type 1 and
+        /// the single token of type 2.
+        fn contract_with_tokens() -> Erc1155 {
+            let mut contract = Erc1155::new();
+            assert_eq!(contract.mint(alice(), 1, 100), Ok(()));
+            assert_eq!(contract.mint(alice(), 2, 1), Ok(()));
+            contract
+        }
+
+        #[ink::test]
+        fn mint_works() {
+            let contract = contract_with_tokens();
+            assert_eq!(contract.balance_of(alice(), 1), 100);
+            assert_eq!(contract.balance_of(alice(), 2), 1);
+            assert_eq!(contract.balance_of(bob(), 1), 0);
+            assert_eq!(
+                contract.balance_of_batch(Vec::from([(alice(), 1), (alice(), 2), (bob(), 1)])),
+                Vec::from([100, 1, 0])
+            );
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_single_event(&emitted_events[0], alice(), None, Some(alice()), 1, 100);
+            assert_transfer_single_event(&emitted_events[1], alice(), None, Some(alice()), 2, 1);
+        }
+
+        #[ink::test]
+        fn mint_requires_owner() {
+            let mut contract = Erc1155::new();
+            set_caller(bob());
+            assert_eq!(contract.mint(bob(), 1, 100), Err(Error::NotOwner));
+            assert_eq!(contract.balance_of(bob(), 1), 0);
+            assert_eq!(recorded_events().len(), 0);
+        }
+
+        #[ink::test]
+        fn mint_overflow_fails() {
+            let mut contract = contract_with_tokens();
+            assert_eq!(
+                contract.mint(alice(), 1, Balance::MAX),
+                Err(Error::Overflow)
+            );
+            assert_eq!(contract.balance_of(alice(), 1), 100);
+            assert_eq!(recorded_events().len(), 2);
+        }
+
+        #[ink::test]
+        fn safe_transfer_from_works() {
+            let mut contract = contract_with_tokens();
+            assert_eq!(
+                contract.safe_transfer_from(alice(), bob(), 1, 40, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(
+                contract.safe_transfer_from(alice(), bob(), 2, 1, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(contract.balance_of(alice(), 1), 60);
+            assert_eq!(contract.balance_of(bob(), 1), 40);
+            assert_eq!(contract.balance_of(bob(), 2), 1);
+
+            // A balance that drops to zero is removed from storage.
+            assert!(!contract.balances.contains((alice(), 2)));
+
+            assert_eq!(
+                contract.safe_transfer_from(alice(), bob(), 2, 1, Vec::new()),
+                Err(Error::InsufficientBalance)
+            );
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 4);
+            assert_transfer_single_event(
+                &emitted_events[2],
+                alice(),
+                Some(alice()),
+                Some(bob()),
+                1,
+                40,
+            );
+            assert_transfer_single_event(
+                &emitted_events[3],
+                alice(),
+                Some(alice()),
+                Some(bob()),
+                2,
+                1,
+            );
+        }
+
+        #[ink::test]
+        fn transfer_requires_approval() {
+            let mut contract = contract_with_tokens();
+            set_caller(bob());
+            assert_eq!(
+                contract.safe_transfer_from(alice(), bob(), 1, 10, Vec::new()),
+                Err(Error::NotApproved)
+            );
+            assert_eq!(
+                contract.safe_batch_transfer_from(
+                    alice(),
+                    bob(),
+                    Vec::from([1]),
+                    Vec::from([10]),
+                    Vec::new()
+                ),
+                Err(Error::NotApproved)
+            );
+            assert_eq!(contract.balance_of(alice(), 1), 100);
+            assert_eq!(recorded_events().len(), 2);
+        }
+
+        #[ink::test]
+        fn operator_can_transfer() {
+            let mut contract = contract_with_tokens();
+            assert_eq!(contract.set_approval_for_all(bob(), true), Ok(()));
+            assert!(contract.is_approved_for_all(alice(), bob()));
+
+            set_caller(bob());
+            assert_eq!(
+                contract.safe_transfer_from(alice(), charlie(), 1, 10, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(contract.balance_of(charlie(), 1), 10);
+
+            set_caller(alice());
+            assert_eq!(contract.set_approval_for_all(bob(), false), Ok(()));
+            set_caller(bob());
+            assert_eq!(
+                contract.safe_transfer_from(alice(), charlie(), 1, 10, Vec::new()),
+                Err(Error::NotApproved)
+            );
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 5);
+            assert_approval_for_all_event(&emitted_events[2], alice(), bob(), true);
+            assert_transfer_single_event(
+                &emitted_events[3],
+                bob(),
+                Some(alice()),
+                Some(charlie()),
+                1,
+                10,
+            );
+            assert_approval_for_all_event(&emitted_events[4], alice(), bob(), false);
+        }
+
+        #[ink::test]
+        fn set_approval_for_all_rejects_self() {
+            let mut contract = Erc1155::new();
+            assert_eq!(
+                contract.set_approval_for_all(alice(), true),
+                Err(Error::SelfApproval)
+            );
+            assert_eq!(recorded_events().len(), 0);
+        }
+
+        #[ink::test]
+        fn safe_batch_transfer_from_works() {
+            let mut contract = contract_with_tokens();
+            assert_eq!(
+                contract.safe_batch_transfer_from(
+                    alice(),
+                    bob(),
+                    Vec::from([1, 2]),
+                    Vec::from([30, 1]),
+                    Vec::new()
+                ),
+                Ok(())
+            );
+            assert_eq!(contract.balance_of(alice(), 1), 70);
+            assert_eq!(contract.balance_of(alice(), 2), 0);
+            assert_eq!(contract.balance_of(bob(), 1), 30);
+            assert_eq!(contract.balance_of(bob(), 2), 1);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 3);
+            assert_transfer_batch_event(
+                &emitted_events[2],
+                alice(),
+                Some(alice()),
+                Some(bob()),
+                &[1, 2],
+                &[30, 1],
+            );
+        }
+
+        #[ink::test]
+        fn batch_with_repeated_token_ids_works() {
+            let mut contract = contract_with_tokens();
+            assert_eq!(
+                contract.safe_batch_transfer_from(
+                    alice(),
+                    bob(),
+                    Vec::from([1, 1, 1]),
+                    Vec::from([10, 20, 30]),
+                    Vec::new()
+                ),
+                Ok(())
+            );
+            assert_eq!(contract.balance_of(alice(), 1), 40);
+            assert_eq!(contract.balance_of(bob(), 1), 60);
+
+            // Each entry alone is covered, but together they exceed the balance.
+            assert_eq!(
+                contract.safe_batch_transfer_from(
+                    alice(),
+                    bob(),
+                    Vec::from([1, 1]),
+                    Vec::from([30, 30]),
+                    Vec::new()
+                ),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(contract.balance_of(alice(), 1), 40);
+            assert_eq!(contract.balance_of(bob(), 1), 60);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 3);
+            assert_transfer_batch_event(
+                &emitted_events[2],
+                alice(),
+                Some(alice()),
+                Some(bob()),
+                &[1, 1, 1],
+                &[10, 20, 30],
+            );
+        }
+
+        #[ink::test]
+        fn batch_is_atomic() {
+            let mut contract = contract_with_tokens();
+
+            // The second transfer cannot be covered, so the first is not applied.
+            assert_eq!(
+                contract.safe_batch_transfer_from(
+                    alice(),
+                    bob(),
+                    Vec::from([1, 2]),
+                    Vec::from([50, 2]),
+                    Vec::new()
+                ),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(contract.balance_of(alice(), 1), 100);
+            assert_eq!(contract.balance_of(alice(), 2), 1);
+            assert_eq!(contract.balance_of(bob(), 1), 0);
+            assert_eq!(recorded_events().len(), 2);
+        }
+
+        #[ink::test]
+        fn batch_overflow_fails() {
+            let mut contract = contract_with_tokens();
+            assert_eq!(
+                contract.safe_batch_transfer_from(
+                    alice(),
+                    bob(),
+                    Vec::from([1, 1]),
+                    Vec::from([Balance::MAX, 1]),
+                    Vec::new()
+                ),
+                Err(Error::Overflow)
+            );
+            assert_eq!(contract.balance_of(alice(), 1), 100);
+            assert_eq!(recorded_events().len(), 2);
+        }
+
+        #[ink::test]
+        fn batch_length_mismatch_fails() {
+            let mut contract = contract_with_tokens();
+            assert_eq!(
+                contract.safe_batch_transfer_from(
+                    alice(),
+                    bob(),
+                    Vec::from([1, 2]),
+                    Vec::from([10]),
+                    Vec::new()
+                ),
+                Err(Error::BatchLengthMismatch)
+            );
+            assert_eq!(contract.balance_of(alice(), 1), 100);
+            assert_eq!(recorded_events().len(), 2);
+        }
+
+        #[ink::test]
+        fn batch_rejects_large_batches() {
+            let mut contract = contract_with_tokens();
+            let token_ids = Vec::from([1; MAX_BATCH_LEN + 1]);
+            let values = Vec::from([0; MAX_BATCH_LEN + 1]);
+            assert_eq!(
+                contract.safe_batch_transfer_from(alice(), bob(), token_ids, values, Vec::new()),
+                Err(Error::BatchTooLarge)
+            );
+            assert_eq!(recorded_events().len(), 2);
+        }
+
+        #[ink::test]
+        fn empty_batch_emits_empty_event() {
+            let mut contract = contract_with_tokens();
+            assert_eq!(
+                contract.safe_batch_transfer_from(
+                    alice(),
+                    bob(),
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new()
+                ),
+                Ok(())
+            );
+            assert_eq!(contract.balance_of(alice(), 1), 100);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 3);
+            assert_transfer_batch_event(
+                &emitted_events[2],
+                alice(),
+                Some(alice()),
+                Some(bob()),
+                &[],
+                &[],
+            );
+        }
+
+        #[ink::test]
+        fn batch_to_self_keeps_balances() {
+            let mut contract = contract_with_tokens();
+            assert_eq!(
+                contract.safe_batch_transfer_from(
+                    alice(),
+                    alice(),
+                    Vec::from([1, 2]),
+                    Vec::from([100, 1]),
+                    Vec::new()
+                ),
+                Ok(())
+            );
+            assert_eq!(contract.balance_of(alice(), 1), 100);
+            assert_eq!(contract.balance_of(alice(), 2), 1);
+
+            // Sending more than the balance to oneself still fails.
+            assert_eq!(
+                contract.safe_batch_transfer_from(
+                    alice(),
+                    alice(),
+                    Vec::from([1]),
+                    Vec::from([101]),
+                    Vec::new()
+                ),
+                Err(Error::InsufficientBalance)
+            );
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 3);
+            assert_transfer_batch_event(
+                &emitted_events[2],
+                alice(),
+                Some(alice()),
+                Some(alice()),
+                &[1, 2],
+                &[100, 1],
+            );
+        }
+
+        #[test]
+        fn on_received_outcome_works() {
+            use crate::ON_RECEIVED_SELECTOR;
+
+            assert_eq!(on_received_outcome(Ok(Ok(ON_RECEIVED_SELECTOR))), Ok(()));
+            assert_eq!(
+                on_received_outcome(Ok(Ok([0; 4]))),
+                Err(Error::TransferRejected)
+            );
+            assert_eq!(
+                on_received_outcome(Ok(Err(ink::LangError::CouldNotReadInput))),
+                Err(Error::TransferRejected)
+            );
+            assert_eq!(
+                on_received_outcome(Err(ink::env::Error::CalleeReverted)),
+                Err(Error::TransferRejected)
+            );
+        }
+    }
+
+    /// End-to-end tests of the `on_received` hook. They deploy the receiver in
+    /// `erc1155-receiver.rs`, which must be a dev-dependency.
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use erc1155_receiver::ReceiverRef;
+        use ink_e2e::build_message;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test(additional_contracts = "../erc1155-receiver/Cargo.toml")]
+        async fn e2e_batch_transfer_calls_on_received(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+
+            let constructor = Erc1155Ref::new();
+            let erc1155_id = client
+                .instantiate("erc1155", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate `erc1155` failed")
+                .account_id;
+
+            for (token_id, value) in [(1, 100), (2, 1)] {
+                let mint = build_message::<Erc1155Ref>(erc1155_id.clone())
+                    .call(|erc1155| erc1155.mint(alice, token_id, value));
+                client
+                    .call(&ink_e2e::alice(), mint, 0, None)
+                    .await
+                    .expect("mint failed");
+            }
+
+            let constructor = ReceiverRef::new(true);
+            let accepting_id = client
+                .instantiate("erc1155_receiver", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate accepting receiver failed")
+                .account_id;
+
+            let constructor = ReceiverRef::new(false);
+            let rejecting_id = client
+                .instantiate("erc1155_receiver", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate rejecting receiver failed")
+                .account_id;
+
+            // The accepting receiver takes the whole batch.
+            let transfer = build_message::<Erc1155Ref>(erc1155_id.clone()).call(|erc1155| {
+                erc1155.safe_batch_transfer_from(
+                    alice,
+                    accepting_id.clone(),
+                    Vec::from([1, 2]),
+                    Vec::from([10, 1]),
+                    Vec::new(),
+                )
+            });
+            let result = client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer to the accepting receiver failed")
+                .return_value();
+            assert_eq!(result, Ok(()));
+
+            let balances = build_message::<Erc1155Ref>(erc1155_id.clone()).call(|erc1155| {
+                erc1155.balance_of_batch(Vec::from([
+                    (alice, 1),
+                    (alice, 2),
+                    (accepting_id.clone(), 1),
+                    (accepting_id.clone(), 2),
+                ]))
+            });
+            let balances = client
+                .call_dry_run(&ink_e2e::alice(), &balances, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balances, Vec::from([90, 0, 10, 1]));
+
+            // The rejecting receiver makes the whole batch fail, and no
+            // balance changes.
+            let transfer = build_message::<Erc1155Ref>(erc1155_id.clone()).call(|erc1155| {
+                erc1155.safe_batch_transfer_from(
+                    alice,
+                    rejecting_id.clone(),
+                    Vec::from([1, 1]),
+                    Vec::from([5, 5]),
+                    Vec::new(),
+                )
+            });
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &transfer, 0, None)
+                .await
+                .return_value();
+            assert_eq!(result, Err(Error::TransferRejected));
+            assert!(client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .is_err());
+
+            let balances = build_message::<Erc1155Ref>(erc1155_id.clone()).call(|erc1155| {
+                erc1155.balance_of_batch(Vec::from([(alice, 1), (rejecting_id.clone(), 1)]))
+            });
+            let balances = client
+                .call_dry_run(&ink_e2e::alice(), &balances, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balances, Vec::from([90, 0]));
+
+            Ok(())
+        }
+    }
+}
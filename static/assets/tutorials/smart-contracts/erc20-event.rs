@@ -113,6 +113,8 @@ mod erc20 {
     mod tests {
         use super::*;
 
+        type Event = <Erc20 as ink::reflect::ContractEventBase>::Type;
+
         // We define some helper Accounts to make our tests more readable
         fn default_accounts() -> ink::env::test::DefaultAccounts<Environment> {
             ink::env::test::default_accounts::<Environment>()
@@ -126,10 +128,95 @@ mod erc20 {
             default_accounts().bob
         }
 
+        fn recorded_events() -> Vec<ink::env::test::EmittedEvent> {
+            ink::env::test::recorded_events().collect()
+        }
+
+        /// Hashes an encoded topic the same way the contract environment does.
+        fn encoded_into_hash<T>(entity: &T) -> Hash
+        where
+            T: scale::Encode,
+        {
+            use ink::{
+                env::hash::{Blake2x256, CryptoHash, HashOutput},
+                primitives::Clear,
+            };
+
+            let mut result = Hash::CLEAR_HASH;
+            let len_result = result.as_ref().len();
+            let encoded = entity.encode();
+            let len_encoded = encoded.len();
+            if len_encoded <= len_result {
+                result.as_mut()[..len_encoded].copy_from_slice(&encoded);
+                return result;
+            }
+            let mut hash_output = <<Blake2x256 as HashOutput>::Type as Default>::default();
+            <Blake2x256 as CryptoHash>::hash(&encoded, &mut hash_output);
+            let copy_len = core::cmp::min(hash_output.len(), len_result);
+            result.as_mut()[0..copy_len].copy_from_slice(&hash_output[0..copy_len]);
+            result
+        }
+
+        fn assert_topics(event: &ink::env::test::EmittedEvent, expected_topics: &[Hash]) {
+            assert_eq!(event.topics.len(), expected_topics.len());
+            for (n, (actual_topic, expected_topic)) in
+                event.topics.iter().zip(expected_topics).enumerate()
+            {
+                let topic = <Hash as scale::Decode>::decode(&mut &actual_topic[..])
+                    .expect("encountered invalid topic encoding");
+                assert_eq!(&topic, expected_topic, "encountered invalid topic at {n}");
+            }
+        }
+
+        fn assert_transfer_event(
+            event: &ink::env::test::EmittedEvent,
+            expected_from: Option<AccountId>,
+            expected_to: Option<AccountId>,
+            expected_value: Balance,
+        ) {
+            use ink::env::topics::PrefixedValue;
+
+            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::Transfer(Transfer { from, to, value }) = decoded_event {
+                assert_eq!(from, expected_from, "encountered invalid Transfer.from");
+                assert_eq!(to, expected_to, "encountered invalid Transfer.to");
+                assert_eq!(value, expected_value, "encountered invalid Transfer.value");
+            } else {
+                panic!("encountered unexpected event kind: expected a Transfer event")
+            }
+
+            assert_topics(
+                event,
+                &[
+                    encoded_into_hash(&PrefixedValue {
+                        value: b"Erc20::Transfer",
+                        prefix: b"",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_from,
+                        prefix: b"Erc20::Transfer::from",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_to,
+                        prefix: b"Erc20::Transfer::to",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_value,
+                        prefix: b"Erc20::Transfer::value",
+                    }),
+                ],
+            );
+        }
+
         #[ink::test]
         fn new_works() {
             let contract = Erc20::new(777);
             assert_eq!(contract.total_supply(), 777);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 1);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 777);
         }
 
         #[ink::test]
@@ -138,6 +225,10 @@ mod erc20 {
             assert_eq!(contract.total_supply(), 100);
             assert_eq!(contract.balance_of(alice()), 100);
             assert_eq!(contract.balance_of(bob()), 0);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 1);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
         }
 
         #[ink::test]
@@ -147,6 +238,11 @@ mod erc20 {
             assert!(contract.transfer(bob(), 10).is_ok());
             assert_eq!(contract.balance_of(bob()), 10);
             assert!(contract.transfer(bob(), 100).is_err());
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_transfer_event(&emitted_events[1], Some(alice()), Some(bob()), 10);
         }
     }
 }
@@ -1,7 +1,89 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use ink::prelude::{string::String, vec::Vec};
+use ink::primitives::AccountId;
+
+/// The balance type used by the PSP22 interface.
+pub type Balance = u128;
+
+/// The PSP22 error type.
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP22Error {
+    /// Custom error type for cases not covered by the standard.
+    Custom(String),
+    /// Returned if not enough balance to fulfill a request is available.
+    InsufficientBalance,
+    /// Returned if not enough allowance to fulfill a request is available.
+    InsufficientAllowance,
+    /// Returned if the recipient's address is zero.
+    ZeroRecipientAddress,
+    /// Returned if the sender's address is zero.
+    ZeroSenderAddress,
+    /// Returned if a safe transfer check fails.
+    SafeTransferCheckFailed(String),
+}
+
+/// The PSP22 fungible token interface.
+///
+/// The selectors match the ones defined by the PSP22 standard so that any
+/// contract or dapp that speaks PSP22 can call this token.
+#[ink::trait_definition]
+pub trait PSP22 {
+    /// Returns the total token supply.
+    #[ink(message, selector = 0x162df8c2)]
+    fn total_supply(&self) -> Balance;
+
+    /// Returns the account balance for the specified `owner`.
+    #[ink(message, selector = 0x6568382f)]
+    fn balance_of(&self, owner: AccountId) -> Balance;
+
+    /// Returns the amount which `spender` is still allowed to withdraw from `owner`.
+    #[ink(message, selector = 0x4d47d921)]
+    fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance;
+
+    /// Transfers `value` tokens from the caller to `to`.
+    #[ink(message, selector = 0xdb20f9f5)]
+    fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+    /// Transfers `value` tokens on the behalf of `from` to `to`.
+    #[ink(message, selector = 0x54b3c76e)]
+    fn transfer_from(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Allows `spender` to withdraw from the caller's account up to `value`.
+    #[ink(message, selector = 0xb20f1bbd)]
+    fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error>;
+
+    /// Atomically increases the allowance granted to `spender` by the caller.
+    #[ink(message, selector = 0x96d6b57a)]
+    fn increase_allowance(
+        &mut self,
+        spender: AccountId,
+        delta_value: Balance,
+    ) -> Result<(), PSP22Error>;
+
+    /// Atomically decreases the allowance granted to `spender` by the caller.
+    #[ink(message, selector = 0xfecb57d5)]
+    fn decrease_allowance(
+        &mut self,
+        spender: AccountId,
+        delta_value: Balance,
+    ) -> Result<(), PSP22Error>;
+}
+
+pub use self::erc20::{Erc20, Erc20Ref, Error};
+
 #[ink::contract]
 mod erc20 {
+    use crate::{PSP22Error, PSP22};
+    use ink::env::hash::Blake2x256;
+    use ink::prelude::{string::String, vec::Vec};
     use ink::storage::Mapping;
 
     #[ink(storage)]
@@ -12,6 +94,30 @@ mod erc20 {
         balances: Mapping<AccountId, Balance>,
         /// Approval spender on behalf of the message's sender.
         allowances: Mapping<(AccountId, AccountId), Balance>,
+        /// The next `permit` nonce of each owner.
+        nonces: Mapping<AccountId, u64>,
+        /// The account allowed to mint and burn tokens.
+        owner: AccountId,
+        /// The optional name of the token.
+        name: Option<String>,
+        /// The optional symbol of the token.
+        symbol: Option<String>,
+        /// The number of decimals used to display balances.
+        decimals: u8,
+        /// Whether transfers and approvals are currently stopped.
+        paused: bool,
+        /// The `(block, balance)` snapshots of each user, indexed in block order.
+        snapshots: Mapping<(AccountId, u32), Snapshot>,
+        /// The number of snapshots recorded for each user.
+        snapshot_counts: Mapping<AccountId, u32>,
+        /// The `(block, total_supply)` snapshots, indexed in block order.
+        supply_snapshots: Mapping<u32, Snapshot>,
+        /// The number of total supply snapshots recorded.
+        supply_snapshot_count: u32,
+        /// The account each user has delegated their voting power to.
+        delegates: Mapping<AccountId, AccountId>,
+        /// The voting power delegated to each account.
+        votes: Mapping<AccountId, Balance>,
     }
 
     #[ink(event)]
@@ -34,6 +140,36 @@ mod erc20 {
         value: Balance,
     }
 
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        account: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct DelegateChanged {
+        #[ink(topic)]
+        delegator: AccountId,
+        #[ink(topic)]
+        from_delegate: Option<AccountId>,
+        #[ink(topic)]
+        to_delegate: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct DelegateVotesChanged {
+        #[ink(topic)]
+        delegate: AccountId,
+        previous_votes: Balance,
+        new_votes: Balance,
+    }
+
     /// Specify ERC-20 error type.
     #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -42,18 +178,104 @@ mod erc20 {
         InsufficientBalance,
         /// Returned if not enough allowance to fulfill a request is available.
         InsufficientAllowance,
+        /// Returned if decreasing an allowance would take it below zero.
+        AllowanceUnderflow,
+        /// Returned if the caller is not the contract owner.
+        NotOwner,
+        /// Returned if a balance, allowance or the total supply would overflow.
+        Overflow,
+        /// Returned if the contract is paused.
+        Paused,
+        /// Returned if a permit is used after its deadline.
+        PermitExpired,
+        /// Returned if a permit signature was not made by the owner.
+        InvalidSignature,
+        /// Returned if a batch holds more than `MAX_BATCH_LEN` transfers.
+        BatchTooLarge,
     }
 
     /// Specify the ERC-20 result type.
     pub type Result<T> = core::result::Result<T, Error>;
 
+    /// The maximum number of transfers accepted by `batch_transfer`.
+    pub const MAX_BATCH_LEN: usize = 100;
+
+    /// A `(block, value)` pair recorded by the balance and supply snapshots.
+    type Snapshot = (BlockNumber, Balance);
+
+    /// Binary searches `count` snapshots stored in block order for the value
+    /// recorded at or before `block`. Returns zero if there is none.
+    fn find_snapshot<F>(count: u32, block: BlockNumber, snapshot_at: F) -> Balance
+    where
+        F: Fn(u32) -> Option<Snapshot>,
+    {
+        let (mut low, mut high) = (0, count);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match snapshot_at(mid) {
+                Some((snapshot_block, _)) if snapshot_block > block => high = mid,
+                _ => low = mid + 1,
+            }
+        }
+
+        low.checked_sub(1)
+            .and_then(snapshot_at)
+            .map(|(_, value)| value)
+            .unwrap_or_default()
+    }
+
+    impl From<Error> for PSP22Error {
+        fn from(error: Error) -> Self {
+            match error {
+                Error::InsufficientBalance => PSP22Error::InsufficientBalance,
+                Error::InsufficientAllowance | Error::AllowanceUnderflow => {
+                    PSP22Error::InsufficientAllowance
+                }
+                Error::NotOwner => PSP22Error::Custom(String::from("NotOwner")),
+                Error::Overflow => PSP22Error::Custom(String::from("Overflow")),
+                Error::Paused => PSP22Error::Custom(String::from("Paused")),
+                Error::PermitExpired => PSP22Error::Custom(String::from("PermitExpired")),
+                Error::InvalidSignature => PSP22Error::Custom(String::from("InvalidSignature")),
+                Error::BatchTooLarge => PSP22Error::Custom(String::from("BatchTooLarge")),
+            }
+        }
+    }
+
     impl Erc20 {
         /// Create a new ERC-20 contract with an initial supply.
         #[ink(constructor)]
         pub fn new(total_supply: Balance) -> Self {
-            let mut balances = Mapping::default();
+            Self::new_with_metadata(total_supply, None, None, 0)
+        }
+
+        /// Create a new ERC-20 contract with an initial supply and token metadata.
+        #[ink(constructor)]
+        pub fn new_with_metadata(
+            total_supply: Balance,
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+        ) -> Self {
             let caller = Self::env().caller();
-            balances.insert(caller, &total_supply);
+            let mut instance = Self {
+                total_supply,
+                balances: Default::default(),
+                allowances: Default::default(),
+                nonces: Default::default(),
+                owner: caller,
+                name,
+                symbol,
+                decimals,
+                paused: false,
+                snapshots: Default::default(),
+                snapshot_counts: Default::default(),
+                supply_snapshots: Default::default(),
+                supply_snapshot_count: 0,
+                delegates: Default::default(),
+                votes: Default::default(),
+            };
+            instance.set_balance(caller, total_supply);
+            instance.snapshot_total_supply();
 
             Self::env().emit_event(Transfer {
                 from: None,
@@ -61,11 +283,141 @@ mod erc20 {
                 value: total_supply,
             });
 
-            Self {
-                total_supply,
-                balances,
-                allowances: Default::default(),
+            instance
+        }
+
+        /// Returns the token name, if one was set.
+        #[ink(message)]
+        pub fn token_name(&self) -> Option<String> {
+            self.name.clone()
+        }
+
+        /// Returns the token symbol, if one was set.
+        #[ink(message)]
+        pub fn token_symbol(&self) -> Option<String> {
+            self.symbol.clone()
+        }
+
+        /// Returns the number of decimals used to display balances.
+        #[ink(message)]
+        pub fn token_decimals(&self) -> u8 {
+            self.decimals
+        }
+
+        /// Returns the account that owns the contract.
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Transfers ownership of the contract to `new_owner`.
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<()> {
+            self.ensure_owner()?;
+            self.owner = new_owner;
+            Ok(())
+        }
+
+        /// Creates `value` new tokens and assigns them to `to`.
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            self.ensure_owner()?;
+
+            let total_supply = self
+                .total_supply
+                .checked_add(value)
+                .ok_or(Error::Overflow)?;
+            let to_balance = self
+                .balance_of(to)
+                .checked_add(value)
+                .ok_or(Error::Overflow)?;
+
+            self.set_balance(to, to_balance);
+            self.total_supply = total_supply;
+            self.snapshot_total_supply();
+
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(to),
+                value,
+            });
+
+            self.move_voting_power(None, self.delegates(to), value)
+        }
+
+        /// Destroys `value` tokens held by `from`.
+        #[ink(message)]
+        pub fn burn(&mut self, from: AccountId, value: Balance) -> Result<()> {
+            self.ensure_owner()?;
+
+            let from_balance = self
+                .balance_of(from)
+                .checked_sub(value)
+                .ok_or(Error::InsufficientBalance)?;
+            // The total supply is the sum of all balances, so it covers any
+            // amount that `from` holds.
+            let total_supply = self
+                .total_supply
+                .checked_sub(value)
+                .ok_or(Error::InsufficientBalance)?;
+
+            self.set_balance(from, from_balance);
+            self.total_supply = total_supply;
+            self.snapshot_total_supply();
+
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: None,
+                value,
+            });
+
+            self.move_voting_power(self.delegates(from), None, value)
+        }
+
+        /// Returns `true` if transfers and approvals are stopped.
+        #[ink(message)]
+        pub fn paused(&self) -> bool {
+            self.paused
+        }
+
+        /// Stops all transfers and approvals until `unpause` is called.
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.paused = true;
+
+            self.env().emit_event(Paused {
+                account: self.env().caller(),
+            });
+
+            Ok(())
+        }
+
+        /// Resumes transfers and approvals after a `pause`.
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<()> {
+            self.ensure_owner()?;
+            self.paused = false;
+
+            self.env().emit_event(Unpaused {
+                account: self.env().caller(),
+            });
+
+            Ok(())
+        }
+
+        fn ensure_owner(&self) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            Ok(())
+        }
+
+        fn ensure_not_paused(&self) -> Result<()> {
+            if self.paused {
+                return Err(Error::Paused);
             }
+            Ok(())
         }
 
         /// Returns the total token supply.
@@ -82,10 +434,39 @@ mod erc20 {
 
         #[ink(message)]
         pub fn transfer(&mut self, to: AccountId, value: Balance) -> Result<()> {
+            self.ensure_not_paused()?;
             let from = self.env().caller();
             self.transfer_from_to(&from, &to, value)
         }
 
+        /// Transfers tokens from the caller to each recipient in `transfers`.
+        ///
+        /// Either every transfer succeeds or none of them is applied.
+        #[ink(message)]
+        pub fn batch_transfer(&mut self, transfers: Vec<(AccountId, Balance)>) -> Result<()> {
+            self.ensure_not_paused()?;
+            if transfers.len() > MAX_BATCH_LEN {
+                return Err(Error::BatchTooLarge);
+            }
+
+            // Check the whole batch up front so that no transfer is applied
+            // when the caller cannot cover all of them.
+            let from = self.env().caller();
+            let total = transfers
+                .iter()
+                .try_fold(0 as Balance, |total, (_, value)| total.checked_add(*value))
+                .ok_or(Error::Overflow)?;
+            if self.balance_of(from) < total {
+                return Err(Error::InsufficientBalance);
+            }
+
+            for (to, value) in transfers {
+                self.transfer_from_to(&from, &to, value)?;
+            }
+
+            Ok(())
+        }
+
         fn transfer_from_to(
             &mut self,
             from: &AccountId,
@@ -93,16 +474,26 @@ mod erc20 {
             value: Balance,
         ) -> Result<()> {
             let from_balance = self.balance_of(*from);
-            if from_balance < value {
-                return Err(Error::InsufficientBalance);
-            }
+            let new_from_balance = from_balance
+                .checked_sub(value)
+                .ok_or(Error::InsufficientBalance)?;
+
+            // Compute the receiver's balance before touching storage so that an
+            // overflow leaves both balances unchanged. A transfer to oneself
+            // leaves the balance as it was.
+            let new_to_balance = if from == to {
+                from_balance
+            } else {
+                self.balance_of(*to)
+                    .checked_add(value)
+                    .ok_or(Error::Overflow)?
+            };
 
             // Update the sender's balance.
-            self.balances.insert(&from, &(from_balance - value));
+            self.set_balance(*from, new_from_balance);
 
             // Update the receiver's balance.
-            let to_balance = self.balance_of(*to);
-            self.balances.insert(&to, &(to_balance + value));
+            self.set_balance(*to, new_to_balance);
 
             self.env().emit_event(Transfer {
                 from: Some(*from),
@@ -110,9 +501,150 @@ mod erc20 {
                 value,
             });
 
+            self.move_voting_power(self.delegates(*from), self.delegates(*to), value)
+        }
+
+        /// Returns the balance of `owner` at the end of the given `block`.
+        #[ink(message)]
+        pub fn balance_of_at(&self, owner: AccountId, block: BlockNumber) -> Balance {
+            let count = self.snapshot_counts.get(owner).unwrap_or_default();
+            find_snapshot(count, block, |index| self.snapshots.get((owner, index)))
+        }
+
+        /// Returns the total token supply at the end of the given `block`.
+        #[ink(message)]
+        pub fn total_supply_at(&self, block: BlockNumber) -> Balance {
+            find_snapshot(self.supply_snapshot_count, block, |index| {
+                self.supply_snapshots.get(index)
+            })
+        }
+
+        /// Stores the new balance of `owner` and records it in a snapshot for
+        /// the current block.
+        ///
+        /// A snapshot is kept even when the balance drops to zero, because
+        /// `balance_of_at` must report the zero for later blocks. The account
+        /// therefore keeps paying a deposit for its snapshot history, and only
+        /// the deposit of the `balances` entry itself is refunded.
+        fn set_balance(&mut self, owner: AccountId, value: Balance) {
+            // Zero balances are removed so that their storage deposit is refunded.
+            // `balance_of` still returns 0 for them.
+            if value == 0 {
+                self.balances.remove(owner);
+            } else {
+                self.balances.insert(owner, &value);
+            }
+
+            // Several updates within one block only keep the last balance.
+            let block = self.env().block_number();
+            let count = self.snapshot_counts.get(owner).unwrap_or_default();
+            let last_block = count
+                .checked_sub(1)
+                .and_then(|last| self.snapshots.get((owner, last)))
+                .map(|(last_block, _)| last_block);
+            if last_block == Some(block) {
+                self.snapshots.insert((owner, count - 1), &(block, value));
+            } else {
+                self.snapshots.insert((owner, count), &(block, value));
+                self.snapshot_counts.insert(owner, &(count + 1));
+            }
+        }
+
+        /// Sets the allowance `owner` has granted to `spender`.
+        fn set_allowance(&mut self, owner: AccountId, spender: AccountId, value: Balance) {
+            // Like balances, zero allowances are removed from storage.
+            if value == 0 {
+                self.allowances.remove((owner, spender));
+            } else {
+                self.allowances.insert((owner, spender), &value);
+            }
+        }
+
+        /// Records the current total supply in a snapshot for the current block.
+        fn snapshot_total_supply(&mut self) {
+            let block = self.env().block_number();
+            let value = self.total_supply;
+            let count = self.supply_snapshot_count;
+            let last_block = count
+                .checked_sub(1)
+                .and_then(|last| self.supply_snapshots.get(last))
+                .map(|(last_block, _)| last_block);
+            if last_block == Some(block) {
+                self.supply_snapshots.insert(count - 1, &(block, value));
+            } else {
+                self.supply_snapshots.insert(count, &(block, value));
+                self.supply_snapshot_count = count + 1;
+            }
+        }
+
+        /// Returns the account that `account` has delegated its votes to.
+        #[ink(message)]
+        pub fn delegates(&self, account: AccountId) -> Option<AccountId> {
+            self.delegates.get(account)
+        }
+
+        /// Returns the voting power currently delegated to `account`.
+        #[ink(message)]
+        pub fn get_votes(&self, account: AccountId) -> Balance {
+            self.votes.get(account).unwrap_or_default()
+        }
+
+        /// Delegates the caller's voting power to `delegatee`.
+        ///
+        /// Accounts have no voting power until they delegate, which includes
+        /// delegating to themselves.
+        #[ink(message)]
+        pub fn delegate(&mut self, delegatee: AccountId) -> Result<()> {
+            let delegator = self.env().caller();
+            let from_delegate = self.delegates(delegator);
+            self.delegates.insert(delegator, &delegatee);
+
+            self.env().emit_event(DelegateChanged {
+                delegator,
+                from_delegate,
+                to_delegate: delegatee,
+            });
+
+            let balance = self.balance_of(delegator);
+            self.move_voting_power(from_delegate, Some(delegatee), balance)
+        }
+
+        /// Moves `value` votes from the `from` delegate to the `to` delegate.
+        fn move_voting_power(
+            &mut self,
+            from: Option<AccountId>,
+            to: Option<AccountId>,
+            value: Balance,
+        ) -> Result<()> {
+            if from == to || value == 0 {
+                return Ok(());
+            }
+
+            if let Some(delegate) = from {
+                let previous_votes = self.get_votes(delegate);
+                let new_votes = previous_votes.checked_sub(value).ok_or(Error::Overflow)?;
+                self.set_votes(delegate, previous_votes, new_votes);
+            }
+
+            if let Some(delegate) = to {
+                let previous_votes = self.get_votes(delegate);
+                let new_votes = previous_votes.checked_add(value).ok_or(Error::Overflow)?;
+                self.set_votes(delegate, previous_votes, new_votes);
+            }
+
             Ok(())
         }
 
+        fn set_votes(&mut self, delegate: AccountId, previous_votes: Balance, new_votes: Balance) {
+            self.votes.insert(delegate, &new_votes);
+
+            self.env().emit_event(DelegateVotesChanged {
+                delegate,
+                previous_votes,
+                new_votes,
+            });
+        }
+
         /// Transfers tokens on the behalf of the `from` account to the `to account
         #[ink(message)]
         pub fn transfer_from(
@@ -121,26 +653,30 @@ mod erc20 {
             to: AccountId,
             value: Balance,
         ) -> Result<()> {
+            self.ensure_not_paused()?;
+
             // Ensure that a sufficient allowance exists.
             let caller = self.env().caller();
-            let allowance = self.allowance(from.clone(), caller.clone());
-            if allowance < value {
-                return Err(Error::InsufficientAllowance);
-            }
+            let allowance = self
+                .allowance(from, caller)
+                .checked_sub(value)
+                .ok_or(Error::InsufficientAllowance)?;
 
             self.transfer_from_to(&from, &to, value)?;
 
             // Decrease the value of the allowance and transfer the tokens.
-            self.allowances.insert((from, caller), &(allowance - value));
+            self.set_allowance(from, caller, allowance);
 
             Ok(())
         }
 
         #[ink(message)]
         pub fn approve(&mut self, spender: AccountId, value: Balance) -> Result<()> {
+            self.ensure_not_paused()?;
+
             // Record the new allowance.
             let owner = self.env().caller();
-            self.allowances.insert(&(owner, spender), &value);
+            self.set_allowance(owner, spender, value);
 
             // Notify offchain users of the approval and report success.
             self.env().emit_event(Approval {
@@ -152,75 +688,1478 @@ mod erc20 {
             Ok(())
         }
 
+        /// Atomically increases the allowance granted to `spender` by the caller.
         #[ink(message)]
-        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
-            self.allowances.get((owner, spender)).unwrap_or_default()
-        }
-    }
-
-    #[cfg(test)]
-    mod tests {
-        use super::*;
+        pub fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> Result<()> {
+            self.ensure_not_paused()?;
+            let owner = self.env().caller();
+            let allowance = self.allowance(owner, spender);
+            let value = allowance.checked_add(delta_value).ok_or(Error::Overflow)?;
+            self.set_allowance(owner, spender, value);
 
-        // We define some helper Accounts to make our tests more readable
-        fn default_accounts() -> ink::env::test::DefaultAccounts<Environment> {
-            ink::env::test::default_accounts::<Environment>()
-        }
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
 
-        fn alice() -> AccountId {
-            default_accounts().alice
+            Ok(())
         }
 
-        fn bob() -> AccountId {
-            default_accounts().bob
-        }
+        /// Atomically decreases the allowance granted to `spender` by the caller.
+        #[ink(message)]
+        pub fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> Result<()> {
+            self.ensure_not_paused()?;
+            let owner = self.env().caller();
+            let allowance = self.allowance(owner, spender);
+            let value = allowance
+                .checked_sub(delta_value)
+                .ok_or(Error::AllowanceUnderflow)?;
+            self.set_allowance(owner, spender, value);
 
-        #[ink::test]
-        fn new_works() {
-            let contract = Erc20::new(777);
-            assert_eq!(contract.total_supply(), 777);
-        }
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
 
-        #[ink::test]
-        fn balance_works() {
-            let contract = Erc20::new(100);
-            assert_eq!(contract.total_supply(), 100);
-            assert_eq!(contract.balance_of(alice()), 100);
-            assert_eq!(contract.balance_of(bob()), 0);
+            Ok(())
         }
 
-        #[ink::test]
-        fn transfer_works() {
-            let mut contract = Erc20::new(100);
-            assert_eq!(contract.balance_of(alice()), 100);
-            assert!(contract.transfer(bob(), 10).is_ok());
-            assert_eq!(contract.balance_of(bob()), 10);
-            assert!(contract.transfer(bob(), 100).is_err());
+        #[ink(message)]
+        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get((owner, spender)).unwrap_or_default()
         }
 
-        #[ink::test]
-        fn transfer_from_works() {
-            let mut contract = Erc20::new(100);
-            assert_eq!(contract.balance_of(alice()), 100);
-            let _ = contract.approve(alice(), 20);
-            let _ = contract.transfer_from(alice(), bob(), 10);
-            assert_eq!(contract.balance_of(bob()), 10);
+        /// Returns the nonce that the next `permit` signed by `owner` must use.
+        #[ink(message)]
+        pub fn nonces(&self, owner: AccountId) -> u64 {
+            self.nonces.get(owner).unwrap_or_default()
         }
 
-        #[ink::test]
-        fn allowances_works() {
-            let mut contract = Erc20::new(100);
-            assert_eq!(contract.balance_of(alice()), 100);
-            let _ = contract.approve(alice(), 200);
-            assert_eq!(contract.allowance(alice(), alice()), 200);
+        /// Sets the allowance of `spender` over the tokens of `owner` using a
+        /// signature made by `owner`, so that `owner` does not have to submit
+        /// an `approve` call.
+        ///
+        /// The `signature` is an ECDSA signature over the Blake2x256 hash of the
+        /// SCALE-encoded tuple `(contract, owner, spender, value, nonce, deadline)`,
+        /// where `owner` is the account derived from the signer's public key.
+        #[ink(message)]
+        pub fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: Timestamp,
+            signature: [u8; 65],
+        ) -> Result<()> {
+            self.ensure_not_paused()?;
 
-            assert!(contract.transfer_from(alice(), bob(), 50).is_ok());
-            assert_eq!(contract.balance_of(bob()), 50);
-            assert_eq!(contract.allowance(alice(), alice()), 150);
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::PermitExpired);
+            }
+
+            let nonce = self.nonces(owner);
+            let message_hash = self.env().hash_encoded::<Blake2x256, _>(&(
+                self.env().account_id(),
+                owner,
+                spender,
+                value,
+                nonce,
+                deadline,
+            ));
+
+            // Recover the signer and map its public key to an account the same
+            // way Substrate does for ECDSA keys.
+            let public_key = self
+                .env()
+                .ecdsa_recover(&signature, &message_hash)
+                .map_err(|_| Error::InvalidSignature)?;
+            let signer = self.env().hash_bytes::<Blake2x256>(&public_key);
+            if AccountId::from(signer) != owner {
+                return Err(Error::InvalidSignature);
+            }
+
+            let next_nonce = nonce.checked_add(1).ok_or(Error::Overflow)?;
+            self.nonces.insert(owner, &next_nonce);
+            self.set_allowance(owner, spender, value);
+
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
+
+            Ok(())
+        }
+    }
+
+    impl PSP22 for Erc20 {
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            Erc20::balance_of(self, owner)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            Erc20::allowance(self, owner, spender)
+        }
+
+        #[ink(message)]
+        fn transfer(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> core::result::Result<(), PSP22Error> {
+            Ok(Erc20::transfer(self, to, value)?)
+        }
+
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> core::result::Result<(), PSP22Error> {
+            Ok(Erc20::transfer_from(self, from, to, value)?)
+        }
+
+        #[ink(message)]
+        fn approve(
+            &mut self,
+            spender: AccountId,
+            value: Balance,
+        ) -> core::result::Result<(), PSP22Error> {
+            Ok(Erc20::approve(self, spender, value)?)
+        }
+
+        #[ink(message)]
+        fn increase_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> core::result::Result<(), PSP22Error> {
+            Ok(Erc20::increase_allowance(self, spender, delta_value)?)
+        }
+
+        #[ink(message)]
+        fn decrease_allowance(
+            &mut self,
+            spender: AccountId,
+            delta_value: Balance,
+        ) -> core::result::Result<(), PSP22Error> {
+            Ok(Erc20::decrease_allowance(self, spender, delta_value)?)
+        }
+    }
+
+    /// Unit tests, ending with property tests of the balance and allowance
+    /// invariants that need `proptest` as a dev-dependency.
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use proptest::prelude::*;
+
+        type Event = <Erc20 as ink::reflect::ContractEventBase>::Type;
+
+        // We define some helper Accounts to make our tests more readable
+        fn default_accounts() -> ink::env::test::DefaultAccounts<Environment> {
+            ink::env::test::default_accounts::<Environment>()
+        }
+
+        fn alice() -> AccountId {
+            default_accounts().alice
+        }
+
+        fn bob() -> AccountId {
+            default_accounts().bob
+        }
+
+        /// Compressed public key of the test key that signed `PERMIT_SIGNATURE`.
+        const PERMIT_PUBLIC_KEY: [u8; 33] = [
+            0x03, 0xcb, 0xc9, 0x73, 0xc8, 0x61, 0x61, 0x8f, 0x50, 0x00, 0xae, 0xe0, 0x38, 0xce,
+            0x50, 0x35, 0xc6, 0xbc, 0x29, 0xc2, 0x5c, 0xcb, 0xdc, 0x27, 0xc2, 0x4e, 0x37, 0x4e,
+            0x56, 0xe4, 0x46, 0x33, 0x21,
+        ];
+
+        /// Signature over a permit of 500 tokens for `bob` with nonce 0 and
+        /// deadline 1_000, issued for a contract deployed at `charlie`.
+        const PERMIT_SIGNATURE: [u8; 65] = [
+            0xd3, 0x93, 0x61, 0xba, 0x4e, 0x30, 0x7d, 0x95, 0xfb, 0x8f, 0x66, 0x03, 0x79, 0xfd,
+            0xc8, 0xf5, 0xf6, 0x22, 0x1c, 0x5d, 0x57, 0xe7, 0x21, 0x88, 0x2b, 0x07, 0x18, 0x49,
+            0x41, 0x8d, 0x0b, 0xdc, 0x70, 0x6b, 0x1e, 0xb0, 0xdb, 0x1f, 0x48, 0x7a, 0x3c, 0x70,
+            0x4d, 0xe7, 0xbd, 0xe1, 0xa5, 0xe7, 0x4a, 0x2b, 0xe1, 0x12, 0xef, 0x50, 0x45, 0x68,
+            0x31, 0xf3, 0x13, 0x89, 0x50, 0x28, 0x1b, 0x55, 0x01,
+        ];
+
+        /// Returns the account derived from `PERMIT_PUBLIC_KEY`.
+        fn permit_owner() -> AccountId {
+            let mut output = <Blake2x256 as ink::env::hash::HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&PERMIT_PUBLIC_KEY, &mut output);
+            AccountId::from(output)
+        }
+
+        fn set_caller(caller: AccountId) {
+            ink::env::test::set_caller::<Environment>(caller);
+        }
+
+        fn recorded_events() -> Vec<ink::env::test::EmittedEvent> {
+            ink::env::test::recorded_events().collect()
+        }
+
+        /// Hashes an encoded topic the same way the contract environment does.
+        fn encoded_into_hash<T>(entity: &T) -> Hash
+        where
+            T: scale::Encode,
+        {
+            use ink::{
+                env::hash::{Blake2x256, CryptoHash, HashOutput},
+                primitives::Clear,
+            };
+
+            let mut result = Hash::CLEAR_HASH;
+            let len_result = result.as_ref().len();
+            let encoded = entity.encode();
+            let len_encoded = encoded.len();
+            if len_encoded <= len_result {
+                result.as_mut()[..len_encoded].copy_from_slice(&encoded);
+                return result;
+            }
+            let mut hash_output = <<Blake2x256 as HashOutput>::Type as Default>::default();
+            <Blake2x256 as CryptoHash>::hash(&encoded, &mut hash_output);
+            let copy_len = core::cmp::min(hash_output.len(), len_result);
+            result.as_mut()[0..copy_len].copy_from_slice(&hash_output[0..copy_len]);
+            result
+        }
+
+        fn assert_topics(event: &ink::env::test::EmittedEvent, expected_topics: &[Hash]) {
+            assert_eq!(event.topics.len(), expected_topics.len());
+            for (n, (actual_topic, expected_topic)) in
+                event.topics.iter().zip(expected_topics).enumerate()
+            {
+                let topic = <Hash as scale::Decode>::decode(&mut &actual_topic[..])
+                    .expect("encountered invalid topic encoding");
+                assert_eq!(&topic, expected_topic, "encountered invalid topic at {n}");
+            }
+        }
+
+        fn assert_transfer_event(
+            event: &ink::env::test::EmittedEvent,
+            expected_from: Option<AccountId>,
+            expected_to: Option<AccountId>,
+            expected_value: Balance,
+        ) {
+            use ink::env::topics::PrefixedValue;
+
+            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::Transfer(Transfer { from, to, value }) = decoded_event {
+                assert_eq!(from, expected_from, "encountered invalid Transfer.from");
+                assert_eq!(to, expected_to, "encountered invalid Transfer.to");
+                assert_eq!(value, expected_value, "encountered invalid Transfer.value");
+            } else {
+                panic!("encountered unexpected event kind: expected a Transfer event")
+            }
+
+            assert_topics(
+                event,
+                &[
+                    encoded_into_hash(&PrefixedValue {
+                        value: b"Erc20::Transfer",
+                        prefix: b"",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_from,
+                        prefix: b"Erc20::Transfer::from",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_to,
+                        prefix: b"Erc20::Transfer::to",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_value,
+                        prefix: b"Erc20::Transfer::value",
+                    }),
+                ],
+            );
+        }
+
+        fn assert_approval_event(
+            event: &ink::env::test::EmittedEvent,
+            expected_owner: AccountId,
+            expected_spender: AccountId,
+            expected_value: Balance,
+        ) {
+            use ink::env::topics::PrefixedValue;
+
+            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::Approval(Approval {
+                owner,
+                spender,
+                value,
+            }) = decoded_event
+            {
+                assert_eq!(owner, expected_owner, "encountered invalid Approval.owner");
+                assert_eq!(
+                    spender, expected_spender,
+                    "encountered invalid Approval.spender"
+                );
+                assert_eq!(value, expected_value, "encountered invalid Approval.value");
+            } else {
+                panic!("encountered unexpected event kind: expected an Approval event")
+            }
+
+            assert_topics(
+                event,
+                &[
+                    encoded_into_hash(&PrefixedValue {
+                        value: b"Erc20::Approval",
+                        prefix: b"",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_owner,
+                        prefix: b"Erc20::Approval::owner",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_spender,
+                        prefix: b"Erc20::Approval::spender",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_value,
+                        prefix: b"Erc20::Approval::value",
+                    }),
+                ],
+            );
+        }
+
+        fn assert_paused_event(event: &ink::env::test::EmittedEvent, expected_account: AccountId) {
+            use ink::env::topics::PrefixedValue;
+
+            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::Paused(Paused { account }) = decoded_event {
+                assert_eq!(
+                    account, expected_account,
+                    "encountered invalid Paused.account"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected a Paused event")
+            }
+
+            assert_topics(
+                event,
+                &[
+                    encoded_into_hash(&PrefixedValue {
+                        value: b"Erc20::Paused",
+                        prefix: b"",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_account,
+                        prefix: b"Erc20::Paused::account",
+                    }),
+                ],
+            );
+        }
+
+        fn assert_unpaused_event(
+            event: &ink::env::test::EmittedEvent,
+            expected_account: AccountId,
+        ) {
+            use ink::env::topics::PrefixedValue;
+
+            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::Unpaused(Unpaused { account }) = decoded_event {
+                assert_eq!(
+                    account, expected_account,
+                    "encountered invalid Unpaused.account"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected an Unpaused event")
+            }
+
+            assert_topics(
+                event,
+                &[
+                    encoded_into_hash(&PrefixedValue {
+                        value: b"Erc20::Unpaused",
+                        prefix: b"",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_account,
+                        prefix: b"Erc20::Unpaused::account",
+                    }),
+                ],
+            );
+        }
+
+        fn assert_delegate_changed_event(
+            event: &ink::env::test::EmittedEvent,
+            expected_delegator: AccountId,
+            expected_from_delegate: Option<AccountId>,
+            expected_to_delegate: AccountId,
+        ) {
+            use ink::env::topics::PrefixedValue;
+
+            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::DelegateChanged(DelegateChanged {
+                delegator,
+                from_delegate,
+                to_delegate,
+            }) = decoded_event
+            {
+                assert_eq!(
+                    delegator, expected_delegator,
+                    "encountered invalid DelegateChanged.delegator"
+                );
+                assert_eq!(
+                    from_delegate, expected_from_delegate,
+                    "encountered invalid DelegateChanged.from_delegate"
+                );
+                assert_eq!(
+                    to_delegate, expected_to_delegate,
+                    "encountered invalid DelegateChanged.to_delegate"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected a DelegateChanged event")
+            }
+
+            assert_topics(
+                event,
+                &[
+                    encoded_into_hash(&PrefixedValue {
+                        value: b"Erc20::DelegateChanged",
+                        prefix: b"",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_delegator,
+                        prefix: b"Erc20::DelegateChanged::delegator",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_from_delegate,
+                        prefix: b"Erc20::DelegateChanged::from_delegate",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_to_delegate,
+                        prefix: b"Erc20::DelegateChanged::to_delegate",
+                    }),
+                ],
+            );
+        }
+
+        fn assert_delegate_votes_changed_event(
+            event: &ink::env::test::EmittedEvent,
+            expected_delegate: AccountId,
+            expected_previous_votes: Balance,
+            expected_new_votes: Balance,
+        ) {
+            use ink::env::topics::PrefixedValue;
+
+            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::DelegateVotesChanged(DelegateVotesChanged {
+                delegate,
+                previous_votes,
+                new_votes,
+            }) = decoded_event
+            {
+                assert_eq!(
+                    delegate, expected_delegate,
+                    "encountered invalid DelegateVotesChanged.delegate"
+                );
+                assert_eq!(
+                    previous_votes, expected_previous_votes,
+                    "encountered invalid DelegateVotesChanged.previous_votes"
+                );
+                assert_eq!(
+                    new_votes, expected_new_votes,
+                    "encountered invalid DelegateVotesChanged.new_votes"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected a DelegateVotesChanged event")
+            }
+
+            assert_topics(
+                event,
+                &[
+                    encoded_into_hash(&PrefixedValue {
+                        value: b"Erc20::DelegateVotesChanged",
+                        prefix: b"",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_delegate,
+                        prefix: b"Erc20::DelegateVotesChanged::delegate",
+                    }),
+                ],
+            );
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let contract = Erc20::new(777);
+            assert_eq!(contract.total_supply(), 777);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 1);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 777);
+        }
+
+        #[ink::test]
+        fn new_without_metadata_works() {
+            let contract = Erc20::new(777);
+            assert_eq!(contract.token_name(), None);
+            assert_eq!(contract.token_symbol(), None);
+            assert_eq!(contract.token_decimals(), 0);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 1);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 777);
+        }
+
+        #[ink::test]
+        fn new_with_metadata_works() {
+            let contract = Erc20::new_with_metadata(
+                777,
+                Some(String::from("Tutorial Token")),
+                Some(String::from("TUT")),
+                12,
+            );
+            assert_eq!(contract.total_supply(), 777);
+            assert_eq!(contract.balance_of(alice()), 777);
+            assert_eq!(contract.token_name(), Some(String::from("Tutorial Token")));
+            assert_eq!(contract.token_symbol(), Some(String::from("TUT")));
+            assert_eq!(contract.token_decimals(), 12);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 1);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 777);
+        }
+
+        #[ink::test]
+        fn balance_works() {
+            let contract = Erc20::new(100);
+            assert_eq!(contract.total_supply(), 100);
+            assert_eq!(contract.balance_of(alice()), 100);
+            assert_eq!(contract.balance_of(bob()), 0);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 1);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+        }
+
+        #[ink::test]
+        fn transfer_works() {
+            let mut contract = Erc20::new(100);
+            assert_eq!(contract.balance_of(alice()), 100);
+            assert!(contract.transfer(bob(), 10).is_ok());
+            assert_eq!(contract.balance_of(bob()), 10);
+            assert!(contract.transfer(bob(), 100).is_err());
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_transfer_event(&emitted_events[1], Some(alice()), Some(bob()), 10);
+        }
+
+        #[ink::test]
+        fn transfer_from_works() {
+            let mut contract = Erc20::new(100);
+            assert_eq!(contract.balance_of(alice()), 100);
+            let _ = contract.approve(alice(), 20);
+            let _ = contract.transfer_from(alice(), bob(), 10);
+            assert_eq!(contract.balance_of(bob()), 10);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 3);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_approval_event(&emitted_events[1], alice(), alice(), 20);
+            assert_transfer_event(&emitted_events[2], Some(alice()), Some(bob()), 10);
+        }
+
+        #[ink::test]
+        fn zero_entries_are_removed_from_storage() {
+            let mut contract = Erc20::new(100);
+            assert!(contract.balances.contains(alice()));
+
+            // Transferring the whole balance removes the sender's entry.
+            assert!(contract.transfer(bob(), 100).is_ok());
+            assert!(!contract.balances.contains(alice()));
+            assert!(contract.balances.contains(bob()));
+            assert_eq!(contract.balance_of(alice()), 0);
+
+            // Spending the whole allowance removes the allowance entry.
+            set_caller(bob());
+            assert!(contract.approve(alice(), 40).is_ok());
+            assert!(contract.allowances.contains((bob(), alice())));
+            set_caller(alice());
+            assert!(contract.transfer_from(bob(), alice(), 40).is_ok());
+            assert!(!contract.allowances.contains((bob(), alice())));
+            assert_eq!(contract.allowance(bob(), alice()), 0);
+
+            // Approving zero removes the entry as well.
+            set_caller(bob());
+            assert!(contract.approve(alice(), 10).is_ok());
+            assert!(contract.approve(alice(), 0).is_ok());
+            assert!(!contract.allowances.contains((bob(), alice())));
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 6);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_transfer_event(&emitted_events[1], Some(alice()), Some(bob()), 100);
+            assert_approval_event(&emitted_events[2], bob(), alice(), 40);
+            assert_transfer_event(&emitted_events[3], Some(bob()), Some(alice()), 40);
+            assert_approval_event(&emitted_events[4], bob(), alice(), 10);
+            assert_approval_event(&emitted_events[5], bob(), alice(), 0);
+        }
+
+        #[ink::test]
+        fn allowances_works() {
+            let mut contract = Erc20::new(100);
+            assert_eq!(contract.balance_of(alice()), 100);
+            let _ = contract.approve(alice(), 200);
+            assert_eq!(contract.allowance(alice(), alice()), 200);
+
+            assert!(contract.transfer_from(alice(), bob(), 50).is_ok());
+            assert_eq!(contract.balance_of(bob()), 50);
+            assert_eq!(contract.allowance(alice(), alice()), 150);
 
             assert!(contract.transfer_from(alice(), bob(), 100).is_err());
             assert_eq!(contract.balance_of(bob()), 50);
             assert_eq!(contract.allowance(alice(), alice()), 150);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 3);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_approval_event(&emitted_events[1], alice(), alice(), 200);
+            assert_transfer_event(&emitted_events[2], Some(alice()), Some(bob()), 50);
+        }
+
+        #[ink::test]
+        fn increase_allowance_works() {
+            let mut contract = Erc20::new(100);
+            assert!(contract.approve(bob(), 20).is_ok());
+            assert!(contract.increase_allowance(bob(), 30).is_ok());
+            assert_eq!(contract.allowance(alice(), bob()), 50);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 3);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_approval_event(&emitted_events[1], alice(), bob(), 20);
+            assert_approval_event(&emitted_events[2], alice(), bob(), 50);
+        }
+
+        #[ink::test]
+        fn decrease_allowance_works() {
+            let mut contract = Erc20::new(100);
+            assert!(contract.approve(bob(), 50).is_ok());
+            assert!(contract.decrease_allowance(bob(), 20).is_ok());
+            assert_eq!(contract.allowance(alice(), bob()), 30);
+
+            assert_eq!(
+                contract.decrease_allowance(bob(), 31),
+                Err(Error::AllowanceUnderflow)
+            );
+            assert_eq!(contract.allowance(alice(), bob()), 30);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 3);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_approval_event(&emitted_events[1], alice(), bob(), 50);
+            assert_approval_event(&emitted_events[2], alice(), bob(), 30);
+        }
+
+        #[ink::test]
+        fn mint_works() {
+            let mut contract = Erc20::new(100);
+            assert_eq!(contract.owner(), alice());
+            assert!(contract.mint(bob(), 50).is_ok());
+            assert_eq!(contract.balance_of(bob()), 50);
+            assert_eq!(contract.total_supply(), 150);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_transfer_event(&emitted_events[1], None, Some(bob()), 50);
+        }
+
+        #[ink::test]
+        fn burn_works() {
+            let mut contract = Erc20::new(100);
+            assert!(contract.burn(alice(), 40).is_ok());
+            assert_eq!(contract.balance_of(alice()), 60);
+            assert_eq!(contract.total_supply(), 60);
+            assert_eq!(contract.burn(alice(), 61), Err(Error::InsufficientBalance));
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_transfer_event(&emitted_events[1], Some(alice()), None, 40);
+        }
+
+        #[ink::test]
+        fn mint_and_burn_require_owner() {
+            let mut contract = Erc20::new(100);
+            set_caller(bob());
+            assert_eq!(contract.mint(bob(), 50), Err(Error::NotOwner));
+            assert_eq!(contract.burn(alice(), 50), Err(Error::NotOwner));
+            assert_eq!(contract.total_supply(), 100);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 1);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+        }
+
+        #[ink::test]
+        fn transfer_ownership_works() {
+            let mut contract = Erc20::new(100);
+            assert!(contract.transfer_ownership(bob()).is_ok());
+            assert_eq!(contract.owner(), bob());
+            assert_eq!(contract.mint(alice(), 10), Err(Error::NotOwner));
+
+            set_caller(bob());
+            assert!(contract.mint(alice(), 10).is_ok());
+            assert_eq!(contract.transfer_ownership(alice()), Ok(()));
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_transfer_event(&emitted_events[1], None, Some(alice()), 10);
+        }
+
+        #[ink::test]
+        fn mint_overflow_fails() {
+            let mut contract = Erc20::new(Balance::MAX - 1);
+            assert_eq!(contract.mint(bob(), 2), Err(Error::Overflow));
+            assert_eq!(contract.total_supply(), Balance::MAX - 1);
+            assert_eq!(contract.balance_of(bob()), 0);
+
+            assert!(contract.mint(bob(), 1).is_ok());
+            assert_eq!(contract.total_supply(), Balance::MAX);
+            assert_eq!(contract.mint(alice(), 1), Err(Error::Overflow));
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), Balance::MAX - 1);
+            assert_transfer_event(&emitted_events[1], None, Some(bob()), 1);
+        }
+
+        #[ink::test]
+        fn transfer_near_max_supply_works() {
+            let mut contract = Erc20::new(Balance::MAX);
+            assert!(contract.transfer(bob(), Balance::MAX - 1).is_ok());
+            assert!(contract.transfer(bob(), 1).is_ok());
+            assert_eq!(contract.balance_of(alice()), 0);
+            assert_eq!(contract.balance_of(bob()), Balance::MAX);
+            assert_eq!(contract.transfer(bob(), 1), Err(Error::InsufficientBalance));
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 3);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), Balance::MAX);
+            assert_transfer_event(
+                &emitted_events[1],
+                Some(alice()),
+                Some(bob()),
+                Balance::MAX - 1,
+            );
+            assert_transfer_event(&emitted_events[2], Some(alice()), Some(bob()), 1);
+        }
+
+        #[ink::test]
+        fn transfer_to_self_keeps_balance() {
+            let mut contract = Erc20::new(Balance::MAX);
+            assert!(contract.transfer(alice(), Balance::MAX).is_ok());
+            assert_eq!(contract.balance_of(alice()), Balance::MAX);
+            assert_eq!(contract.total_supply(), Balance::MAX);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), Balance::MAX);
+            assert_transfer_event(
+                &emitted_events[1],
+                Some(alice()),
+                Some(alice()),
+                Balance::MAX,
+            );
+        }
+
+        #[ink::test]
+        fn increase_allowance_overflow_fails() {
+            let mut contract = Erc20::new(100);
+            assert!(contract.approve(bob(), Balance::MAX).is_ok());
+            assert_eq!(contract.increase_allowance(bob(), 1), Err(Error::Overflow));
+            assert_eq!(contract.allowance(alice(), bob()), Balance::MAX);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_approval_event(&emitted_events[1], alice(), bob(), Balance::MAX);
+        }
+
+        #[ink::test]
+        fn psp22_transfer_works() {
+            let mut contract = Erc20::new(100);
+            assert!(PSP22::transfer(&mut contract, bob(), 10, Vec::new()).is_ok());
+            assert_eq!(PSP22::balance_of(&contract, bob()), 10);
+            assert_eq!(PSP22::total_supply(&contract), 100);
+            assert_eq!(
+                PSP22::transfer(&mut contract, bob(), 100, Vec::new()),
+                Err(PSP22Error::InsufficientBalance)
+            );
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_transfer_event(&emitted_events[1], Some(alice()), Some(bob()), 10);
+        }
+
+        #[ink::test]
+        fn psp22_allowances_work() {
+            let mut contract = Erc20::new(100);
+            assert!(PSP22::approve(&mut contract, alice(), 20).is_ok());
+            assert!(PSP22::increase_allowance(&mut contract, alice(), 10).is_ok());
+            assert_eq!(PSP22::allowance(&contract, alice(), alice()), 30);
+            assert!(PSP22::transfer_from(&mut contract, alice(), bob(), 25, Vec::new()).is_ok());
+            assert_eq!(PSP22::allowance(&contract, alice(), alice()), 5);
+            assert_eq!(
+                PSP22::decrease_allowance(&mut contract, alice(), 6),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 4);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_approval_event(&emitted_events[1], alice(), alice(), 20);
+            assert_approval_event(&emitted_events[2], alice(), alice(), 30);
+            assert_transfer_event(&emitted_events[3], Some(alice()), Some(bob()), 25);
+        }
+
+        #[ink::test]
+        fn pause_blocks_transfers_and_approvals() {
+            let mut contract = Erc20::new(100);
+            assert!(contract.approve(bob(), 50).is_ok());
+            assert!(!contract.paused());
+            assert!(contract.pause().is_ok());
+            assert!(contract.paused());
+
+            assert_eq!(contract.transfer(bob(), 10), Err(Error::Paused));
+            assert_eq!(contract.approve(bob(), 10), Err(Error::Paused));
+            assert_eq!(contract.increase_allowance(bob(), 10), Err(Error::Paused));
+            assert_eq!(contract.decrease_allowance(bob(), 10), Err(Error::Paused));
+
+            set_caller(bob());
+            assert_eq!(
+                contract.transfer_from(alice(), bob(), 10),
+                Err(Error::Paused)
+            );
+
+            // Read-only messages keep working while the contract is paused.
+            assert_eq!(contract.total_supply(), 100);
+            assert_eq!(contract.balance_of(alice()), 100);
+            assert_eq!(contract.balance_of(bob()), 0);
+            assert_eq!(contract.allowance(alice(), bob()), 50);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 3);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_approval_event(&emitted_events[1], alice(), bob(), 50);
+            assert_paused_event(&emitted_events[2], alice());
+        }
+
+        #[ink::test]
+        fn unpause_resumes_transfers() {
+            let mut contract = Erc20::new(100);
+            assert!(contract.pause().is_ok());
+            assert_eq!(contract.transfer(bob(), 10), Err(Error::Paused));
+            assert!(contract.unpause().is_ok());
+            assert!(!contract.paused());
+            assert!(contract.transfer(bob(), 10).is_ok());
+            assert_eq!(contract.balance_of(bob()), 10);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 4);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_paused_event(&emitted_events[1], alice());
+            assert_unpaused_event(&emitted_events[2], alice());
+            assert_transfer_event(&emitted_events[3], Some(alice()), Some(bob()), 10);
+        }
+
+        #[ink::test]
+        fn pause_requires_owner() {
+            let mut contract = Erc20::new(100);
+            set_caller(bob());
+            assert_eq!(contract.pause(), Err(Error::NotOwner));
+            assert_eq!(contract.unpause(), Err(Error::NotOwner));
+            assert!(!contract.paused());
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 1);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+        }
+
+        #[ink::test]
+        fn permit_works() {
+            ink::env::test::set_callee::<Environment>(default_accounts().charlie);
+            let mut contract = Erc20::new(100);
+            let owner = permit_owner();
+            assert!(contract.transfer(owner, 60).is_ok());
+
+            assert_eq!(contract.nonces(owner), 0);
+            assert!(contract
+                .permit(owner, bob(), 500, 1_000, PERMIT_SIGNATURE)
+                .is_ok());
+            assert_eq!(contract.allowance(owner, bob()), 500);
+            assert_eq!(contract.nonces(owner), 1);
+
+            // The same signature cannot be replayed once the nonce moved on.
+            assert_eq!(
+                contract.permit(owner, bob(), 500, 1_000, PERMIT_SIGNATURE),
+                Err(Error::InvalidSignature)
+            );
+
+            set_caller(bob());
+            assert!(contract.transfer_from(owner, bob(), 50).is_ok());
+            assert_eq!(contract.balance_of(bob()), 50);
+            assert_eq!(contract.allowance(owner, bob()), 450);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 4);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_transfer_event(&emitted_events[1], Some(alice()), Some(owner), 60);
+            assert_approval_event(&emitted_events[2], owner, bob(), 500);
+            assert_transfer_event(&emitted_events[3], Some(owner), Some(bob()), 50);
+        }
+
+        #[ink::test]
+        fn permit_rejects_expired_deadline() {
+            ink::env::test::set_callee::<Environment>(default_accounts().charlie);
+            let mut contract = Erc20::new(100);
+            ink::env::test::set_block_timestamp::<Environment>(1_001);
+            let owner = permit_owner();
+
+            assert_eq!(
+                contract.permit(owner, bob(), 500, 1_000, PERMIT_SIGNATURE),
+                Err(Error::PermitExpired)
+            );
+            assert_eq!(contract.allowance(owner, bob()), 0);
+            assert_eq!(contract.nonces(owner), 0);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 1);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+        }
+
+        #[ink::test]
+        fn permit_rejects_wrong_signer() {
+            ink::env::test::set_callee::<Environment>(default_accounts().charlie);
+            let mut contract = Erc20::new(100);
+            let owner = permit_owner();
+
+            // A signature by another key, or over other values, is rejected.
+            assert_eq!(
+                contract.permit(alice(), bob(), 500, 1_000, PERMIT_SIGNATURE),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(
+                contract.permit(owner, bob(), 501, 1_000, PERMIT_SIGNATURE),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(contract.allowance(owner, bob()), 0);
+            assert_eq!(contract.allowance(alice(), bob()), 0);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 1);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+        }
+
+        #[ink::test]
+        fn batch_transfer_works() {
+            let mut contract = Erc20::new(100);
+            let charlie = default_accounts().charlie;
+            assert!(contract
+                .batch_transfer(vec![(bob(), 10), (charlie, 20), (bob(), 5)])
+                .is_ok());
+            assert_eq!(contract.balance_of(alice()), 65);
+            assert_eq!(contract.balance_of(bob()), 15);
+            assert_eq!(contract.balance_of(charlie), 20);
+            assert!(contract.batch_transfer(Vec::new()).is_ok());
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 4);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_transfer_event(&emitted_events[1], Some(alice()), Some(bob()), 10);
+            assert_transfer_event(&emitted_events[2], Some(alice()), Some(charlie), 20);
+            assert_transfer_event(&emitted_events[3], Some(alice()), Some(bob()), 5);
+        }
+
+        #[ink::test]
+        fn batch_transfer_is_atomic() {
+            let mut contract = Erc20::new(100);
+            let charlie = default_accounts().charlie;
+            assert_eq!(
+                contract.batch_transfer(vec![(bob(), 60), (charlie, 41)]),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(
+                contract.batch_transfer(vec![(bob(), 1), (charlie, Balance::MAX)]),
+                Err(Error::Overflow)
+            );
+            assert_eq!(contract.balance_of(alice()), 100);
+            assert_eq!(contract.balance_of(bob()), 0);
+            assert_eq!(contract.balance_of(charlie), 0);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 1);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+        }
+
+        #[ink::test]
+        fn batch_transfer_rejects_large_batches() {
+            let mut contract = Erc20::new(1_000);
+            assert_eq!(
+                contract.batch_transfer(vec![(bob(), 1); MAX_BATCH_LEN + 1]),
+                Err(Error::BatchTooLarge)
+            );
+            assert!(contract
+                .batch_transfer(vec![(bob(), 1); MAX_BATCH_LEN])
+                .is_ok());
+            assert_eq!(contract.balance_of(bob()), MAX_BATCH_LEN as Balance);
+            assert_eq!(recorded_events().len(), MAX_BATCH_LEN + 1);
+        }
+
+        #[ink::test]
+        fn balance_snapshots_work() {
+            let mut contract = Erc20::new(100);
+            ink::env::test::advance_block::<Environment>();
+            assert!(contract.transfer(bob(), 10).is_ok());
+            ink::env::test::advance_block::<Environment>();
+            assert!(contract.transfer(bob(), 20).is_ok());
+            assert!(contract.transfer(bob(), 5).is_ok());
+            ink::env::test::advance_block::<Environment>();
+            ink::env::test::advance_block::<Environment>();
+            assert!(contract.transfer(bob(), 1).is_ok());
+
+            assert_eq!(contract.balance_of_at(alice(), 0), 100);
+            assert_eq!(contract.balance_of_at(bob(), 0), 0);
+            assert_eq!(contract.balance_of_at(alice(), 1), 90);
+            assert_eq!(contract.balance_of_at(bob(), 1), 10);
+            assert_eq!(contract.balance_of_at(bob(), 2), 35);
+            assert_eq!(contract.balance_of_at(bob(), 3), 35);
+            assert_eq!(contract.balance_of_at(bob(), 4), 36);
+            assert_eq!(contract.balance_of_at(bob(), 100), 36);
+            assert_eq!(contract.balance_of_at(default_accounts().charlie, 4), 0);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 5);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_transfer_event(&emitted_events[1], Some(alice()), Some(bob()), 10);
+            assert_transfer_event(&emitted_events[2], Some(alice()), Some(bob()), 20);
+            assert_transfer_event(&emitted_events[3], Some(alice()), Some(bob()), 5);
+            assert_transfer_event(&emitted_events[4], Some(alice()), Some(bob()), 1);
+        }
+
+        #[ink::test]
+        fn total_supply_snapshots_work() {
+            let mut contract = Erc20::new(100);
+            ink::env::test::advance_block::<Environment>();
+            assert!(contract.mint(bob(), 50).is_ok());
+            ink::env::test::advance_block::<Environment>();
+            assert!(contract.burn(alice(), 30).is_ok());
+
+            assert_eq!(contract.total_supply_at(0), 100);
+            assert_eq!(contract.total_supply_at(1), 150);
+            assert_eq!(contract.total_supply_at(2), 120);
+            assert_eq!(contract.balance_of_at(bob(), 0), 0);
+            assert_eq!(contract.balance_of_at(bob(), 1), 50);
+            assert_eq!(contract.balance_of_at(alice(), 1), 100);
+            assert_eq!(contract.balance_of_at(alice(), 2), 70);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 3);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_transfer_event(&emitted_events[1], None, Some(bob()), 50);
+            assert_transfer_event(&emitted_events[2], Some(alice()), None, 30);
+        }
+
+        #[ink::test]
+        fn delegate_works() {
+            let mut contract = Erc20::new(100);
+            assert_eq!(contract.delegates(alice()), None);
+            assert_eq!(contract.get_votes(alice()), 0);
+
+            assert!(contract.delegate(alice()).is_ok());
+            assert_eq!(contract.delegates(alice()), Some(alice()));
+            assert_eq!(contract.get_votes(alice()), 100);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 3);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_delegate_changed_event(&emitted_events[1], alice(), None, alice());
+            assert_delegate_votes_changed_event(&emitted_events[2], alice(), 0, 100);
+        }
+
+        #[ink::test]
+        fn votes_follow_transfers() {
+            let mut contract = Erc20::new(100);
+            let charlie = default_accounts().charlie;
+            assert!(contract.delegate(alice()).is_ok());
+            set_caller(bob());
+            assert!(contract.delegate(charlie).is_ok());
+
+            set_caller(alice());
+            assert!(contract.transfer(bob(), 30).is_ok());
+            assert_eq!(contract.get_votes(alice()), 70);
+            assert_eq!(contract.get_votes(charlie), 30);
+            assert_eq!(contract.get_votes(bob()), 0);
+
+            set_caller(bob());
+            assert!(contract.delegate(bob()).is_ok());
+            assert_eq!(contract.get_votes(charlie), 0);
+            assert_eq!(contract.get_votes(bob()), 30);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 10);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_delegate_changed_event(&emitted_events[1], alice(), None, alice());
+            assert_delegate_votes_changed_event(&emitted_events[2], alice(), 0, 100);
+            assert_delegate_changed_event(&emitted_events[3], bob(), None, charlie);
+            assert_transfer_event(&emitted_events[4], Some(alice()), Some(bob()), 30);
+            assert_delegate_votes_changed_event(&emitted_events[5], alice(), 100, 70);
+            assert_delegate_votes_changed_event(&emitted_events[6], charlie, 0, 30);
+            assert_delegate_changed_event(&emitted_events[7], bob(), Some(charlie), bob());
+            assert_delegate_votes_changed_event(&emitted_events[8], charlie, 30, 0);
+            assert_delegate_votes_changed_event(&emitted_events[9], bob(), 0, 30);
+        }
+
+        #[ink::test]
+        fn votes_follow_mint_and_burn() {
+            let mut contract = Erc20::new(100);
+            assert!(contract.delegate(alice()).is_ok());
+            assert!(contract.mint(alice(), 50).is_ok());
+            assert_eq!(contract.get_votes(alice()), 150);
+            assert!(contract.burn(alice(), 120).is_ok());
+            assert_eq!(contract.get_votes(alice()), 30);
+
+            // Undelegated balances carry no voting power.
+            assert!(contract.mint(bob(), 50).is_ok());
+            assert_eq!(contract.get_votes(bob()), 0);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 8);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 100);
+            assert_delegate_changed_event(&emitted_events[1], alice(), None, alice());
+            assert_delegate_votes_changed_event(&emitted_events[2], alice(), 0, 100);
+            assert_transfer_event(&emitted_events[3], None, Some(alice()), 50);
+            assert_delegate_votes_changed_event(&emitted_events[4], alice(), 100, 150);
+            assert_transfer_event(&emitted_events[5], Some(alice()), None, 120);
+            assert_delegate_votes_changed_event(&emitted_events[6], alice(), 150, 30);
+            assert_transfer_event(&emitted_events[7], None, Some(bob()), 50);
+        }
+
+        /// An operation called by one of the `DefaultAccounts`. Accounts are
+        /// referred to by their index in `accounts()`.
+        #[derive(Debug, Clone)]
+        enum Operation {
+            Transfer {
+                from: usize,
+                to: usize,
+                value: Balance,
+            },
+            Approve {
+                owner: usize,
+                spender: usize,
+                value: Balance,
+            },
+            TransferFrom {
+                spender: usize,
+                from: usize,
+                to: usize,
+                value: Balance,
+            },
+        }
+
+        fn accounts() -> [AccountId; 6] {
+            let accounts = default_accounts();
+            [
+                accounts.alice,
+                accounts.bob,
+                accounts.charlie,
+                accounts.django,
+                accounts.eve,
+                accounts.frank,
+            ]
+        }
+
+        fn operation() -> impl Strategy<Value = Operation> {
+            let account = 0..6usize;
+            let value = 0..=1_500u128;
+            prop_oneof![
+                (account.clone(), account.clone(), value.clone())
+                    .prop_map(|(from, to, value)| Operation::Transfer { from, to, value }),
+                (account.clone(), account.clone(), value.clone()).prop_map(
+                    |(owner, spender, value)| Operation::Approve {
+                        owner,
+                        spender,
+                        value
+                    }
+                ),
+                (account.clone(), account.clone(), account, value).prop_map(
+                    |(spender, from, to, value)| Operation::TransferFrom {
+                        spender,
+                        from,
+                        to,
+                        value
+                    }
+                ),
+            ]
+        }
+
+        /// Calls `operation` on `contract` as the account that performs it.
+        fn apply(contract: &mut Erc20, operation: &Operation) -> Result<()> {
+            let accounts = accounts();
+            match *operation {
+                Operation::Transfer { from, to, value } => {
+                    set_caller(accounts[from]);
+                    contract.transfer(accounts[to], value)
+                }
+                Operation::Approve {
+                    owner,
+                    spender,
+                    value,
+                } => {
+                    set_caller(accounts[owner]);
+                    contract.approve(accounts[spender], value)
+                }
+                Operation::TransferFrom {
+                    spender,
+                    from,
+                    to,
+                    value,
+                } => {
+                    set_caller(accounts[spender]);
+                    contract.transfer_from(accounts[from], accounts[to], value)
+                }
+            }
+        }
+
+        proptest! {
+            #[test]
+            fn balances_always_sum_to_total_supply(
+                total_supply in 0..=1_000u128,
+                operations in prop::collection::vec(operation(), 1..50),
+            ) {
+                ink::env::test::run_test::<Environment, _>(|_| {
+                    let mut contract = Erc20::new(total_supply);
+                    for operation in &operations {
+                        let _ = apply(&mut contract, operation);
+                        let sum: Balance = accounts()
+                            .iter()
+                            .map(|account| contract.balance_of(*account))
+                            .sum();
+                        assert_eq!(sum, contract.total_supply());
+                        assert_eq!(contract.total_supply(), total_supply);
+                    }
+                    Ok(())
+                })
+                .unwrap();
+            }
+
+            #[test]
+            fn transfer_from_never_increases_allowance(
+                total_supply in 0..=1_000u128,
+                operations in prop::collection::vec(operation(), 1..50),
+            ) {
+                ink::env::test::run_test::<Environment, _>(|_| {
+                    let accounts = accounts();
+                    let mut contract = Erc20::new(total_supply);
+                    for operation in &operations {
+                        let before = match *operation {
+                            Operation::TransferFrom { spender, from, .. } => {
+                                Some(contract.allowance(accounts[from], accounts[spender]))
+                            }
+                            _ => None,
+                        };
+                        let result = apply(&mut contract, operation);
+
+                        if let Operation::TransferFrom {
+                            spender,
+                            from,
+                            value,
+                            ..
+                        } = *operation
+                        {
+                            let before = before.expect("recorded for every `transfer_from`");
+                            let after = contract.allowance(accounts[from], accounts[spender]);
+                            match result {
+                                Ok(()) => assert_eq!(after, before - value),
+                                Err(_) => assert_eq!(after, before),
+                            }
+                        }
+                    }
+                    Ok(())
+                })
+                .unwrap();
+            }
+        }
+    }
+
+    /// End-to-end tests that run against a local `substrate-contracts-node`.
+    ///
+    /// These require `ink_e2e` as a dev-dependency and an `e2e-tests = []`
+    /// feature in `Cargo.toml`. Run them with `cargo test --features e2e-tests`.
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::{build_message, events::ContractEmitted};
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+        type Event = <Erc20 as ink::reflect::ContractEventBase>::Type;
+
+        /// Decodes the events emitted by the contract during an extrinsic.
+        fn contract_events(
+            events: &ink_e2e::ExtrinsicEvents<ink_e2e::PolkadotConfig>,
+        ) -> Vec<Event> {
+            events
+                .find::<ContractEmitted<ink::env::DefaultEnvironment>>()
+                .map(|event| {
+                    let event = event.expect("failed to read `ContractEmitted`");
+                    <Event as scale::Decode>::decode(&mut &event.data[..])
+                        .expect("failed to decode contract event")
+                })
+                .collect()
+        }
+
+        #[ink_e2e::test]
+        async fn e2e_transfer_works(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let constructor = Erc20Ref::new(1_000);
+            let contract_id = client
+                .instantiate("erc20", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+
+            let transfer = build_message::<Erc20Ref>(contract_id.clone())
+                .call(|erc20| erc20.transfer(bob, 100));
+            let result = client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+
+            match &contract_events(&result.events)[..] {
+                [Event::Transfer(event)] => {
+                    assert_eq!(event.from, Some(alice));
+                    assert_eq!(event.to, Some(bob));
+                    assert_eq!(event.value, 100);
+                }
+                events => panic!("unexpected events: {:?}", events.len()),
+            }
+
+            let balance_of =
+                build_message::<Erc20Ref>(contract_id.clone()).call(|erc20| erc20.balance_of(bob));
+            let balance = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, 100);
+
+            let transfer = build_message::<Erc20Ref>(contract_id.clone())
+                .call(|erc20| erc20.transfer(alice, 101));
+            let result = client
+                .call_dry_run(&ink_e2e::bob(), &transfer, 0, None)
+                .await
+                .return_value();
+            assert_eq!(result, Err(Error::InsufficientBalance));
+
+            Ok(())
+        }
+
+        #[ink_e2e::test]
+        async fn e2e_allowances_work(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let constructor = Erc20Ref::new(1_000);
+            let contract_id = client
+                .instantiate("erc20", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let charlie = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+
+            // Bob cannot spend Alice's tokens before she approves him.
+            let transfer_from = build_message::<Erc20Ref>(contract_id.clone())
+                .call(|erc20| erc20.transfer_from(alice, charlie, 50));
+            let result = client
+                .call_dry_run(&ink_e2e::bob(), &transfer_from, 0, None)
+                .await
+                .return_value();
+            assert_eq!(result, Err(Error::InsufficientAllowance));
+
+            let approve = build_message::<Erc20Ref>(contract_id.clone())
+                .call(|erc20| erc20.approve(bob, 200));
+            let result = client
+                .call(&ink_e2e::alice(), approve, 0, None)
+                .await
+                .expect("approve failed");
+
+            match &contract_events(&result.events)[..] {
+                [Event::Approval(event)] => {
+                    assert_eq!(event.owner, alice);
+                    assert_eq!(event.spender, bob);
+                    assert_eq!(event.value, 200);
+                }
+                events => panic!("unexpected events: {:?}", events.len()),
+            }
+
+            let result = client
+                .call(&ink_e2e::bob(), transfer_from, 0, None)
+                .await
+                .expect("transfer_from failed");
+
+            match &contract_events(&result.events)[..] {
+                [Event::Transfer(event)] => {
+                    assert_eq!(event.from, Some(alice));
+                    assert_eq!(event.to, Some(charlie));
+                    assert_eq!(event.value, 50);
+                }
+                events => panic!("unexpected events: {:?}", events.len()),
+            }
+
+            let allowance = build_message::<Erc20Ref>(contract_id.clone())
+                .call(|erc20| erc20.allowance(alice, bob));
+            let remaining = client
+                .call_dry_run(&ink_e2e::alice(), &allowance, 0, None)
+                .await
+                .return_value();
+            assert_eq!(remaining, 150);
+
+            let balance_of = build_message::<Erc20Ref>(contract_id.clone())
+                .call(|erc20| erc20.balance_of(charlie));
+            let balance = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, 50);
+
+            Ok(())
         }
     }
 }
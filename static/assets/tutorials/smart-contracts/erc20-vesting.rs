@@ -0,0 +1,401 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Releases `Erc20` tokens to beneficiaries following linear vesting schedules.
+///
+/// This contract calls the token through `erc20::Erc20Ref`, so the contract in
+/// `erc20-final.rs` must be added as a dependency with the `ink-as-dependency`
+/// feature enabled.
+#[ink::contract]
+mod vesting {
+    use erc20::Erc20Ref;
+    use ink::storage::Mapping;
+
+    /// A linear vesting schedule.
+    ///
+    /// Nothing vests before `start + cliff`. After that, tokens vest linearly
+    /// from `start` until `start + duration`, when all of `total` is vested.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct VestingSchedule {
+        /// The total amount of tokens to vest.
+        pub total: Balance,
+        /// The amount of tokens already released.
+        pub released: Balance,
+        /// The time the schedule starts, in milliseconds.
+        pub start: Timestamp,
+        /// The time after `start` before which nothing vests, in milliseconds.
+        pub cliff: Timestamp,
+        /// The time after `start` at which everything is vested, in milliseconds.
+        pub duration: Timestamp,
+    }
+
+    impl VestingSchedule {
+        /// Returns the amount of tokens vested at time `now`.
+        fn vested_at(&self, now: Timestamp) -> Balance {
+            let elapsed = now.saturating_sub(self.start);
+            if elapsed < self.cliff {
+                return 0;
+            }
+            if elapsed >= self.duration {
+                return self.total;
+            }
+
+            // Computes `total * elapsed / duration` without overflowing.
+            let duration = Balance::from(self.duration);
+            let elapsed = Balance::from(elapsed);
+            (self.total / duration) * elapsed + (self.total % duration) * elapsed / duration
+        }
+    }
+
+    #[ink(storage)]
+    pub struct Vesting {
+        /// The token that is being vested.
+        token: Erc20Ref,
+        /// The account allowed to create schedules.
+        owner: AccountId,
+        /// The vesting schedule of each beneficiary.
+        schedules: Mapping<AccountId, VestingSchedule>,
+    }
+
+    #[ink(event)]
+    pub struct ScheduleCreated {
+        #[ink(topic)]
+        beneficiary: AccountId,
+        total: Balance,
+        start: Timestamp,
+        cliff: Timestamp,
+        duration: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct Released {
+        #[ink(topic)]
+        beneficiary: AccountId,
+        value: Balance,
+    }
+
+    /// Specify the vesting error type.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not the contract owner.
+        NotOwner,
+        /// Returned if the beneficiary already has a schedule.
+        ScheduleExists,
+        /// Returned if the duration is zero or shorter than the cliff.
+        InvalidSchedule,
+        /// Returned if the caller has no tokens to release.
+        NothingToRelease,
+        /// Returned if the token contract refused the transfer.
+        TransferFailed(erc20::Error),
+    }
+
+    /// Specify the vesting result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Vesting {
+        /// Create a new vesting contract for the `Erc20` token at `token`.
+        #[ink(constructor)]
+        pub fn new(token: AccountId) -> Self {
+            Self {
+                token: ink::env::call::FromAccountId::from_account_id(token),
+                owner: Self::env().caller(),
+                schedules: Mapping::default(),
+            }
+        }
+
+        /// Creates a vesting schedule for `beneficiary`.
+        ///
+        /// The contract must hold enough tokens to cover every schedule.
+        #[ink(message)]
+        pub fn add_schedule(
+            &mut self,
+            beneficiary: AccountId,
+            total: Balance,
+            start: Timestamp,
+            cliff: Timestamp,
+            duration: Timestamp,
+        ) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            if duration == 0 || cliff > duration {
+                return Err(Error::InvalidSchedule);
+            }
+            if self.schedules.contains(beneficiary) {
+                return Err(Error::ScheduleExists);
+            }
+
+            let schedule = VestingSchedule {
+                total,
+                released: 0,
+                start,
+                cliff,
+                duration,
+            };
+            self.schedules.insert(beneficiary, &schedule);
+
+            // The `Erc20` dependency also implements `EmitEvent` for the
+            // environment, so the call has to name this contract's impl.
+            <ink::EnvAccess<Environment> as ink::codegen::EmitEvent<Vesting>>::emit_event(
+                self.env(),
+                ScheduleCreated {
+                    beneficiary,
+                    total,
+                    start,
+                    cliff,
+                    duration,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Returns the vesting schedule of `beneficiary`, if any.
+        #[ink(message)]
+        pub fn schedule(&self, beneficiary: AccountId) -> Option<VestingSchedule> {
+            self.schedules.get(beneficiary)
+        }
+
+        /// Returns the amount of tokens `beneficiary` can release right now.
+        #[ink(message)]
+        pub fn releasable(&self, beneficiary: AccountId) -> Balance {
+            self.schedules
+                .get(beneficiary)
+                .map(|schedule| {
+                    schedule.vested_at(self.env().block_timestamp()) - schedule.released
+                })
+                .unwrap_or_default()
+        }
+
+        /// Transfers all vested but not yet released tokens to the caller.
+        #[ink(message)]
+        pub fn release(&mut self) -> Result<()> {
+            let beneficiary = self.env().caller();
+            let value = self.releasable(beneficiary);
+            if value == 0 {
+                return Err(Error::NothingToRelease);
+            }
+
+            // Record the release before calling into the token contract.
+            let mut schedule = self
+                .schedules
+                .get(beneficiary)
+                .expect("a releasable amount implies a schedule");
+            schedule.released += value;
+            self.schedules.insert(beneficiary, &schedule);
+
+            self.token
+                .transfer(beneficiary, value)
+                .map_err(Error::TransferFailed)?;
+
+            <ink::EnvAccess<Environment> as ink::codegen::EmitEvent<Vesting>>::emit_event(
+                self.env(),
+                Released { beneficiary, value },
+            );
+
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn default_accounts() -> ink::env::test::DefaultAccounts<Environment> {
+            ink::env::test::default_accounts::<Environment>()
+        }
+
+        fn bob() -> AccountId {
+            default_accounts().bob
+        }
+
+        fn token() -> AccountId {
+            default_accounts().django
+        }
+
+        fn set_caller(caller: AccountId) {
+            ink::env::test::set_caller::<Environment>(caller);
+        }
+
+        fn set_block_timestamp(timestamp: Timestamp) {
+            ink::env::test::set_block_timestamp::<Environment>(timestamp);
+        }
+
+        #[ink::test]
+        fn add_schedule_works() {
+            let mut contract = Vesting::new(token());
+            assert_eq!(contract.schedule(bob()), None);
+            assert!(contract.add_schedule(bob(), 1_000, 100, 200, 1_000).is_ok());
+            assert_eq!(
+                contract.schedule(bob()),
+                Some(VestingSchedule {
+                    total: 1_000,
+                    released: 0,
+                    start: 100,
+                    cliff: 200,
+                    duration: 1_000,
+                })
+            );
+            assert_eq!(
+                contract.add_schedule(bob(), 1_000, 100, 200, 1_000),
+                Err(Error::ScheduleExists)
+            );
+            assert_eq!(ink::env::test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn add_schedule_rejects_invalid_schedules() {
+            let mut contract = Vesting::new(token());
+            assert_eq!(
+                contract.add_schedule(bob(), 1_000, 0, 0, 0),
+                Err(Error::InvalidSchedule)
+            );
+            assert_eq!(
+                contract.add_schedule(bob(), 1_000, 0, 11, 10),
+                Err(Error::InvalidSchedule)
+            );
+
+            set_caller(bob());
+            assert_eq!(
+                contract.add_schedule(bob(), 1_000, 0, 0, 10),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(ink::env::test::recorded_events().count(), 0);
+        }
+
+        #[ink::test]
+        fn releasable_vests_linearly_after_cliff() {
+            let mut contract = Vesting::new(token());
+            assert!(contract.add_schedule(bob(), 1_000, 100, 200, 1_000).is_ok());
+
+            set_block_timestamp(0);
+            assert_eq!(contract.releasable(bob()), 0);
+            set_block_timestamp(299);
+            assert_eq!(contract.releasable(bob()), 0);
+            set_block_timestamp(300);
+            assert_eq!(contract.releasable(bob()), 200);
+            set_block_timestamp(650);
+            assert_eq!(contract.releasable(bob()), 550);
+            set_block_timestamp(1_100);
+            assert_eq!(contract.releasable(bob()), 1_000);
+            set_block_timestamp(5_000);
+            assert_eq!(contract.releasable(bob()), 1_000);
+
+            assert_eq!(contract.releasable(default_accounts().charlie), 0);
+        }
+
+        #[ink::test]
+        fn vesting_does_not_overflow_for_large_totals() {
+            let schedule = VestingSchedule {
+                total: Balance::MAX,
+                released: 0,
+                start: 0,
+                cliff: 0,
+                duration: 4,
+            };
+            assert_eq!(schedule.vested_at(1), Balance::MAX / 4);
+            assert_eq!(schedule.vested_at(2), Balance::MAX / 2);
+            assert_eq!(schedule.vested_at(4), Balance::MAX);
+        }
+
+        #[ink::test]
+        fn release_without_vested_tokens_fails() {
+            let mut contract = Vesting::new(token());
+            assert!(contract.add_schedule(bob(), 1_000, 100, 200, 1_000).is_ok());
+
+            set_caller(bob());
+            set_block_timestamp(150);
+            assert_eq!(contract.release(), Err(Error::NothingToRelease));
+
+            set_caller(default_accounts().charlie);
+            assert_eq!(contract.release(), Err(Error::NothingToRelease));
+        }
+    }
+
+    /// End-to-end tests of a release, which moves tokens through a call to the
+    /// `Erc20` contract and so cannot run off-chain.
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::build_message;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test(additional_contracts = "../erc20/Cargo.toml")]
+        async fn e2e_release_transfers_vested_tokens(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+
+            let constructor = Erc20Ref::new(1_000);
+            let erc20_id = client
+                .instantiate("erc20", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate `erc20` failed")
+                .account_id;
+
+            let constructor = VestingRef::new(erc20_id.clone());
+            let vesting_id = client
+                .instantiate("vesting", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate `vesting` failed")
+                .account_id;
+
+            // Fund the vesting contract with the tokens of the schedule.
+            let transfer = build_message::<Erc20Ref>(erc20_id.clone())
+                .call(|erc20| erc20.transfer(vesting_id.clone(), 300));
+            client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+
+            // The chain's timestamp lies far past a schedule starting at 0, so
+            // the cliff has passed and every token is vested.
+            let add_schedule = build_message::<VestingRef>(vesting_id.clone())
+                .call(|vesting| vesting.add_schedule(bob, 300, 0, 1_000, 2_000));
+            client
+                .call(&ink_e2e::alice(), add_schedule, 0, None)
+                .await
+                .expect("add_schedule failed");
+
+            let release =
+                build_message::<VestingRef>(vesting_id.clone()).call(|vesting| vesting.release());
+            client
+                .call(&ink_e2e::bob(), release, 0, None)
+                .await
+                .expect("release failed");
+
+            let balance_of =
+                build_message::<Erc20Ref>(erc20_id.clone()).call(|erc20| erc20.balance_of(bob));
+            let balance = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, 300);
+
+            let schedule = build_message::<VestingRef>(vesting_id.clone())
+                .call(|vesting| vesting.schedule(bob));
+            let schedule = client
+                .call_dry_run(&ink_e2e::alice(), &schedule, 0, None)
+                .await
+                .return_value()
+                .expect("bob has a schedule");
+            assert_eq!(schedule.released, 300);
+
+            // Nothing is left to release.
+            let release =
+                build_message::<VestingRef>(vesting_id.clone()).call(|vesting| vesting.release());
+            let result = client
+                .call_dry_run(&ink_e2e::bob(), &release, 0, None)
+                .await
+                .return_value();
+            assert_eq!(result, Err(Error::NothingToRelease));
+
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,646 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// A non-fungible token following the ERC-721 standard.
+///
+/// Every token is identified by a `TokenId` and has exactly one owner. Like the
+/// `Erc20` in `erc20-final.rs`, only the account that instantiated the contract
+/// can mint new tokens.
+#[ink::contract]
+mod erc721 {
+    use ink::prelude::string::String;
+    use ink::storage::Mapping;
+
+    /// The identifier of a token.
+    pub type TokenId = u32;
+
+    /// Create storage for a simple ERC-721 contract.
+    #[ink(storage)]
+    pub struct Erc721 {
+        /// Mapping from token to owner.
+        token_owner: Mapping<TokenId, AccountId>,
+        /// Mapping from token to the account approved to transfer it.
+        token_approvals: Mapping<TokenId, AccountId>,
+        /// Mapping from owner to the number of tokens they own.
+        owned_tokens_count: Mapping<AccountId, u32>,
+        /// Mapping from owner to the operators they approved for all tokens.
+        operator_approvals: Mapping<(AccountId, AccountId), ()>,
+        /// Mapping from token to the URI of its metadata.
+        token_uris: Mapping<TokenId, String>,
+        /// The account allowed to mint tokens.
+        owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        #[ink(topic)]
+        id: TokenId,
+    }
+
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        approved: AccountId,
+        #[ink(topic)]
+        id: TokenId,
+    }
+
+    #[ink(event)]
+    pub struct ApprovalForAll {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        approved: bool,
+    }
+
+    /// Specify ERC-721 error type.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not the contract owner.
+        NotOwner,
+        /// Returned if the account is not the owner of the token.
+        NotTokenOwner,
+        /// Returned if the caller is neither the token owner nor approved.
+        NotApproved,
+        /// Returned if a token with the given ID already exists.
+        TokenExists,
+        /// Returned if no token with the given ID exists.
+        TokenNotFound,
+        /// Returned if the caller tries to approve itself as an operator.
+        SelfApproval,
+        /// Returned if a token count does not fit in a `u32`.
+        Overflow,
+    }
+
+    /// Specify the ERC-721 result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Erc721 {
+        /// Create a new ERC-721 contract without any tokens.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                token_owner: Mapping::default(),
+                token_approvals: Mapping::default(),
+                owned_tokens_count: Mapping::default(),
+                operator_approvals: Mapping::default(),
+                token_uris: Mapping::default(),
+                owner: Self::env().caller(),
+            }
+        }
+
+        /// Returns the number of tokens owned by `owner`.
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> u32 {
+            self.owned_tokens_count.get(owner).unwrap_or_default()
+        }
+
+        /// Returns the owner of the token, if it exists.
+        #[ink(message)]
+        pub fn owner_of(&self, id: TokenId) -> Option<AccountId> {
+            self.token_owner.get(id)
+        }
+
+        /// Returns the account approved to transfer the token, if any.
+        #[ink(message)]
+        pub fn get_approved(&self, id: TokenId) -> Option<AccountId> {
+            self.token_approvals.get(id)
+        }
+
+        /// Returns `true` if `operator` may transfer all tokens of `owner`.
+        #[ink(message)]
+        pub fn is_approved_for_all(&self, owner: AccountId, operator: AccountId) -> bool {
+            self.operator_approvals.contains((owner, operator))
+        }
+
+        /// Returns the URI of the token's metadata, if the token exists.
+        #[ink(message)]
+        pub fn token_uri(&self, id: TokenId) -> Option<String> {
+            self.token_uris.get(id)
+        }
+
+        /// Creates the token `id` with metadata at `uri` and gives it to `to`.
+        ///
+        /// Only the contract owner can mint tokens.
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, id: TokenId, uri: String) -> Result<()> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            if self.token_owner.contains(id) {
+                return Err(Error::TokenExists);
+            }
+
+            let count = self.balance_of(to).checked_add(1).ok_or(Error::Overflow)?;
+            self.owned_tokens_count.insert(to, &count);
+            self.token_owner.insert(id, &to);
+            self.token_uris.insert(id, &uri);
+
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(to),
+                id,
+            });
+
+            Ok(())
+        }
+
+        /// Transfers the token `id` from `from` to `to`.
+        ///
+        /// The caller must own the token, be approved for it, or be an operator
+        /// of `from`. Any approval for the token is cleared.
+        #[ink(message)]
+        pub fn transfer_from(&mut self, from: AccountId, to: AccountId, id: TokenId) -> Result<()> {
+            let owner = self.owner_of(id).ok_or(Error::TokenNotFound)?;
+            if owner != from {
+                return Err(Error::NotTokenOwner);
+            }
+            if !self.approved_or_owner(self.env().caller(), owner, id) {
+                return Err(Error::NotApproved);
+            }
+
+            // Compute the receiver's count before touching storage so that an
+            // overflow leaves both counts unchanged.
+            if from != to {
+                let to_count = self.balance_of(to).checked_add(1).ok_or(Error::Overflow)?;
+                self.set_count(from, self.balance_of(from) - 1);
+                self.set_count(to, to_count);
+            }
+            self.token_approvals.remove(id);
+            self.token_owner.insert(id, &to);
+
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                id,
+            });
+
+            Ok(())
+        }
+
+        /// Approves `to` to transfer the token `id`.
+        ///
+        /// The caller must own the token or be an operator of its owner.
+        #[ink(message)]
+        pub fn approve(&mut self, to: AccountId, id: TokenId) -> Result<()> {
+            let owner = self.owner_of(id).ok_or(Error::TokenNotFound)?;
+            let caller = self.env().caller();
+            if caller != owner && !self.is_approved_for_all(owner, caller) {
+                return Err(Error::NotTokenOwner);
+            }
+
+            self.token_approvals.insert(id, &to);
+
+            self.env().emit_event(Approval {
+                owner,
+                approved: to,
+                id,
+            });
+
+            Ok(())
+        }
+
+        /// Allows or disallows `operator` to transfer all tokens of the caller.
+        #[ink(message)]
+        pub fn set_approval_for_all(&mut self, operator: AccountId, approved: bool) -> Result<()> {
+            let owner = self.env().caller();
+            if operator == owner {
+                return Err(Error::SelfApproval);
+            }
+
+            if approved {
+                self.operator_approvals.insert((owner, operator), &());
+            } else {
+                self.operator_approvals.remove((owner, operator));
+            }
+
+            self.env().emit_event(ApprovalForAll {
+                owner,
+                operator,
+                approved,
+            });
+
+            Ok(())
+        }
+
+        /// Returns `true` if `caller` may transfer the token `id` of `owner`.
+        fn approved_or_owner(&self, caller: AccountId, owner: AccountId, id: TokenId) -> bool {
+            caller == owner
+                || self.get_approved(id) == Some(caller)
+                || self.is_approved_for_all(owner, caller)
+        }
+
+        /// Stores the token count of `owner`, removing it when it drops to zero.
+        fn set_count(&mut self, owner: AccountId, count: u32) {
+            if count == 0 {
+                self.owned_tokens_count.remove(owner);
+            } else {
+                self.owned_tokens_count.insert(owner, &count);
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        type Event = <Erc721 as ink::reflect::ContractEventBase>::Type;
+
+        fn default_accounts() -> ink::env::test::DefaultAccounts<Environment> {
+            ink::env::test::default_accounts::<Environment>()
+        }
+
+        fn alice() -> AccountId {
+            default_accounts().alice
+        }
+
+        fn bob() -> AccountId {
+            default_accounts().bob
+        }
+
+        fn charlie() -> AccountId {
+            default_accounts().charlie
+        }
+
+        fn set_caller(caller: AccountId) {
+            ink::env::test::set_caller::<Environment>(caller);
+        }
+
+        fn recorded_events() -> Vec<ink::env::test::EmittedEvent> {
+            ink::env::test::recorded_events().collect()
+        }
+
+        /// Hashes an encoded topic the same way the contract environment does.
+        fn encoded_into_hash<T>(entity: &T) -> Hash
+        where
+            T: scale::Encode,
+        {
+            use ink::{
+                env::hash::{Blake2x256, CryptoHash, HashOutput},
+                primitives::Clear,
+            };
+
+            let mut result = Hash::CLEAR_HASH;
+            let len_result = result.as_ref().len();
+            let encoded = entity.encode();
+            let len_encoded = encoded.len();
+            if len_encoded <= len_result {
+                result.as_mut()[..len_encoded].copy_from_slice(&encoded);
+                return result;
+            }
+            let mut hash_output = <<Blake2x256 as HashOutput>::Type as Default>::default();
+            <Blake2x256 as CryptoHash>::hash(&encoded, &mut hash_output);
+            let copy_len = core::cmp::min(hash_output.len(), len_result);
+            result.as_mut()[0..copy_len].copy_from_slice(&hash_output[0..copy_len]);
+            result
+        }
+
+        fn assert_topics(event: &ink::env::test::EmittedEvent, expected_topics: &[Hash]) {
+            assert_eq!(event.topics.len(), expected_topics.len());
+            for (n, (actual_topic, expected_topic)) in
+                event.topics.iter().zip(expected_topics).enumerate()
+            {
+                let topic = <Hash as scale::Decode>::decode(&mut &actual_topic[..])
+                    .expect("encountered invalid topic encoding");
+                assert_eq!(&topic, expected_topic, "encountered invalid topic at {n}");
+            }
+        }
+
+        fn assert_transfer_event(
+            event: &ink::env::test::EmittedEvent,
+            expected_from: Option<AccountId>,
+            expected_to: Option<AccountId>,
+            expected_id: TokenId,
+        ) {
+            use ink::env::topics::PrefixedValue;
+
+            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::Transfer(Transfer { from, to, id }) = decoded_event {
+                assert_eq!(from, expected_from, "encountered invalid Transfer.from");
+                assert_eq!(to, expected_to, "encountered invalid Transfer.to");
+                assert_eq!(id, expected_id, "encountered invalid Transfer.id");
+            } else {
+                panic!("encountered unexpected event kind: expected a Transfer event")
+            }
+
+            assert_topics(
+                event,
+                &[
+                    encoded_into_hash(&PrefixedValue {
+                        value: b"Erc721::Transfer",
+                        prefix: b"",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_from,
+                        prefix: b"Erc721::Transfer::from",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_to,
+                        prefix: b"Erc721::Transfer::to",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_id,
+                        prefix: b"Erc721::Transfer::id",
+                    }),
+                ],
+            );
+        }
+
+        fn assert_approval_event(
+            event: &ink::env::test::EmittedEvent,
+            expected_owner: AccountId,
+            expected_approved: AccountId,
+            expected_id: TokenId,
+        ) {
+            use ink::env::topics::PrefixedValue;
+
+            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::Approval(Approval {
+                owner,
+                approved,
+                id,
+            }) = decoded_event
+            {
+                assert_eq!(owner, expected_owner, "encountered invalid Approval.owner");
+                assert_eq!(
+                    approved, expected_approved,
+                    "encountered invalid Approval.approved"
+                );
+                assert_eq!(id, expected_id, "encountered invalid Approval.id");
+            } else {
+                panic!("encountered unexpected event kind: expected an Approval event")
+            }
+
+            assert_topics(
+                event,
+                &[
+                    encoded_into_hash(&PrefixedValue {
+                        value: b"Erc721::Approval",
+                        prefix: b"",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_owner,
+                        prefix: b"Erc721::Approval::owner",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_approved,
+                        prefix: b"Erc721::Approval::approved",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_id,
+                        prefix: b"Erc721::Approval::id",
+                    }),
+                ],
+            );
+        }
+
+        fn assert_approval_for_all_event(
+            event: &ink::env::test::EmittedEvent,
+            expected_owner: AccountId,
+            expected_operator: AccountId,
+            expected_approved: bool,
+        ) {
+            use ink::env::topics::PrefixedValue;
+
+            let decoded_event = <Event as scale::Decode>::decode(&mut &event.data[..])
+                .expect("encountered invalid contract event data buffer");
+            if let Event::ApprovalForAll(ApprovalForAll {
+                owner,
+                operator,
+                approved,
+            }) = decoded_event
+            {
+                assert_eq!(
+                    owner, expected_owner,
+                    "encountered invalid ApprovalForAll.owner"
+                );
+                assert_eq!(
+                    operator, expected_operator,
+                    "encountered invalid ApprovalForAll.operator"
+                );
+                assert_eq!(
+                    approved, expected_approved,
+                    "encountered invalid ApprovalForAll.approved"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected an ApprovalForAll event")
+            }
+
+            assert_topics(
+                event,
+                &[
+                    encoded_into_hash(&PrefixedValue {
+                        value: b"Erc721::ApprovalForAll",
+                        prefix: b"",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_owner,
+                        prefix: b"Erc721::ApprovalForAll::owner",
+                    }),
+                    encoded_into_hash(&PrefixedValue {
+                        value: &expected_operator,
+                        prefix: b"Erc721::ApprovalForAll::operator",
+                    }),
+                ],
+            );
+        }
+
+        /// Creates a contract in which `alice` owns token 1.
+        fn contract_with_token() -> Erc721 {
+            let mut contract = Erc721::new();
+            assert_eq!(
+                contract.mint(alice(), 1, String::from("ipfs://token/1")),
+                Ok(())
+            );
+            contract
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let contract = Erc721::new();
+            assert_eq!(contract.balance_of(alice()), 0);
+            assert_eq!(contract.owner_of(1), None);
+            assert_eq!(contract.token_uri(1), None);
+            assert_eq!(recorded_events().len(), 0);
+        }
+
+        #[ink::test]
+        fn mint_works() {
+            let contract = contract_with_token();
+            assert_eq!(contract.balance_of(alice()), 1);
+            assert_eq!(contract.owner_of(1), Some(alice()));
+            assert_eq!(contract.token_uri(1), Some(String::from("ipfs://token/1")));
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 1);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 1);
+        }
+
+        #[ink::test]
+        fn mint_existing_token_fails() {
+            let mut contract = contract_with_token();
+            assert_eq!(
+                contract.mint(bob(), 1, String::from("ipfs://token/other")),
+                Err(Error::TokenExists)
+            );
+            assert_eq!(contract.owner_of(1), Some(alice()));
+            assert_eq!(contract.balance_of(bob()), 0);
+            assert_eq!(recorded_events().len(), 1);
+        }
+
+        #[ink::test]
+        fn mint_requires_owner() {
+            let mut contract = Erc721::new();
+            set_caller(bob());
+            assert_eq!(
+                contract.mint(bob(), 1, String::from("ipfs://token/1")),
+                Err(Error::NotOwner)
+            );
+            assert_eq!(contract.owner_of(1), None);
+            assert_eq!(recorded_events().len(), 0);
+        }
+
+        #[ink::test]
+        fn transfer_from_works() {
+            let mut contract = contract_with_token();
+            assert_eq!(contract.transfer_from(alice(), bob(), 1), Ok(()));
+            assert_eq!(contract.owner_of(1), Some(bob()));
+            assert_eq!(contract.balance_of(alice()), 0);
+            assert_eq!(contract.balance_of(bob()), 1);
+
+            // A count that drops to zero is removed from storage.
+            assert!(!contract.owned_tokens_count.contains(alice()));
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(&emitted_events[0], None, Some(alice()), 1);
+            assert_transfer_event(&emitted_events[1], Some(alice()), Some(bob()), 1);
+        }
+
+        #[ink::test]
+        fn transfer_to_self_keeps_balance() {
+            let mut contract = contract_with_token();
+            assert_eq!(contract.transfer_from(alice(), alice(), 1), Ok(()));
+            assert_eq!(contract.owner_of(1), Some(alice()));
+            assert_eq!(contract.balance_of(alice()), 1);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_transfer_event(&emitted_events[1], Some(alice()), Some(alice()), 1);
+        }
+
+        #[ink::test]
+        fn transfer_from_fails_for_invalid_tokens() {
+            let mut contract = contract_with_token();
+            assert_eq!(
+                contract.transfer_from(alice(), bob(), 2),
+                Err(Error::TokenNotFound)
+            );
+            assert_eq!(
+                contract.transfer_from(bob(), charlie(), 1),
+                Err(Error::NotTokenOwner)
+            );
+            assert_eq!(contract.owner_of(1), Some(alice()));
+            assert_eq!(recorded_events().len(), 1);
+        }
+
+        #[ink::test]
+        fn transfer_from_requires_approval() {
+            let mut contract = contract_with_token();
+            set_caller(bob());
+            assert_eq!(
+                contract.transfer_from(alice(), bob(), 1),
+                Err(Error::NotApproved)
+            );
+            assert_eq!(contract.owner_of(1), Some(alice()));
+            assert_eq!(recorded_events().len(), 1);
+        }
+
+        #[ink::test]
+        fn approved_account_can_transfer() {
+            let mut contract = contract_with_token();
+            assert_eq!(contract.approve(bob(), 1), Ok(()));
+            assert_eq!(contract.get_approved(1), Some(bob()));
+
+            set_caller(bob());
+            assert_eq!(contract.transfer_from(alice(), charlie(), 1), Ok(()));
+            assert_eq!(contract.owner_of(1), Some(charlie()));
+
+            // The approval is cleared by the transfer.
+            assert_eq!(contract.get_approved(1), None);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 3);
+            assert_approval_event(&emitted_events[1], alice(), bob(), 1);
+            assert_transfer_event(&emitted_events[2], Some(alice()), Some(charlie()), 1);
+        }
+
+        #[ink::test]
+        fn approve_requires_token_owner() {
+            let mut contract = contract_with_token();
+            assert_eq!(contract.approve(bob(), 2), Err(Error::TokenNotFound));
+
+            set_caller(bob());
+            assert_eq!(contract.approve(bob(), 1), Err(Error::NotTokenOwner));
+            assert_eq!(contract.get_approved(1), None);
+            assert_eq!(recorded_events().len(), 1);
+        }
+
+        #[ink::test]
+        fn operator_can_approve_and_transfer() {
+            let mut contract = contract_with_token();
+            assert_eq!(contract.set_approval_for_all(bob(), true), Ok(()));
+            assert!(contract.is_approved_for_all(alice(), bob()));
+
+            set_caller(bob());
+            assert_eq!(contract.approve(charlie(), 1), Ok(()));
+            assert_eq!(contract.transfer_from(alice(), bob(), 1), Ok(()));
+            assert_eq!(contract.owner_of(1), Some(bob()));
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 4);
+            assert_approval_for_all_event(&emitted_events[1], alice(), bob(), true);
+            assert_approval_event(&emitted_events[2], alice(), charlie(), 1);
+            assert_transfer_event(&emitted_events[3], Some(alice()), Some(bob()), 1);
+        }
+
+        #[ink::test]
+        fn revoked_operator_cannot_transfer() {
+            let mut contract = contract_with_token();
+            assert_eq!(contract.set_approval_for_all(bob(), true), Ok(()));
+            assert_eq!(contract.set_approval_for_all(bob(), false), Ok(()));
+            assert!(!contract.is_approved_for_all(alice(), bob()));
+
+            set_caller(bob());
+            assert_eq!(
+                contract.transfer_from(alice(), bob(), 1),
+                Err(Error::NotApproved)
+            );
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 3);
+            assert_approval_for_all_event(&emitted_events[1], alice(), bob(), true);
+            assert_approval_for_all_event(&emitted_events[2], alice(), bob(), false);
+        }
+
+        #[ink::test]
+        fn set_approval_for_all_rejects_self() {
+            let mut contract = Erc721::new();
+            assert_eq!(
+                contract.set_approval_for_all(alice(), true),
+                Err(Error::SelfApproval)
+            );
+            assert!(!contract.is_approved_for_all(alice(), alice()));
+            assert_eq!(recorded_events().len(), 0);
+        }
+    }
+}
@@ -0,0 +1,709 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Holds a buyer's payment until an arbiter releases it to the seller or
+/// refunds it to the buyer.
+///
+/// Payments are made either in the native currency or in an `Erc20` token.
+/// This contract calls the token through `erc20::Erc20Ref`, so the contract in
+/// `erc20-final.rs` must be added as a dependency with the `ink-as-dependency`
+/// feature enabled.
+#[ink::contract]
+mod escrow {
+    use erc20::Erc20Ref;
+    use ink::storage::Mapping;
+
+    /// The identifier of a deal.
+    pub type DealId = u32;
+
+    /// The currency a deal is paid in.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum Asset {
+        /// The native currency of the chain.
+        Native,
+        /// The `Erc20` token of the escrow.
+        Token,
+    }
+
+    /// The state of a deal.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum DealState {
+        /// The payment is held by the escrow.
+        Pending,
+        /// The arbiter paid the seller.
+        Released,
+        /// The arbiter paid the buyer back.
+        Refunded,
+        /// The deadline passed and the buyer was paid back.
+        Expired,
+    }
+
+    /// A payment held by the escrow.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Deal {
+        /// The account that paid and receives refunds.
+        pub buyer: AccountId,
+        /// The account that receives the payment once released.
+        pub seller: AccountId,
+        /// The currency of the payment.
+        pub asset: Asset,
+        /// The amount of the payment.
+        pub amount: Balance,
+        /// The time after which the buyer can take the payment back.
+        pub deadline: Timestamp,
+        /// The state of the deal.
+        pub state: DealState,
+    }
+
+    #[ink(storage)]
+    pub struct Escrow {
+        /// The account that decides whether a payment is released or refunded.
+        arbiter: AccountId,
+        /// The token accepted by `deposit_tokens`.
+        token: Erc20Ref,
+        /// The identifier of the next deal.
+        next_id: DealId,
+        /// The deals of the escrow.
+        deals: Mapping<DealId, Deal>,
+    }
+
+    #[ink(event)]
+    pub struct Deposited {
+        #[ink(topic)]
+        id: DealId,
+        #[ink(topic)]
+        buyer: AccountId,
+        #[ink(topic)]
+        seller: AccountId,
+        asset: Asset,
+        amount: Balance,
+        deadline: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct Released {
+        #[ink(topic)]
+        id: DealId,
+        #[ink(topic)]
+        seller: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Refunded {
+        #[ink(topic)]
+        id: DealId,
+        #[ink(topic)]
+        buyer: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Expired {
+        #[ink(topic)]
+        id: DealId,
+        #[ink(topic)]
+        buyer: AccountId,
+        amount: Balance,
+    }
+
+    /// Specify the escrow error type.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not the arbiter.
+        NotArbiter,
+        /// Returned if a deposit has no value.
+        ZeroAmount,
+        /// Returned if the deadline is not in the future.
+        InvalidDeadline,
+        /// Returned if no deal with the given ID exists.
+        DealNotFound,
+        /// Returned if the deal was already settled.
+        NotPending,
+        /// Returned if the arbiter acts after the deadline.
+        DeadlinePassed,
+        /// Returned if the buyer claims a refund before the deadline.
+        DeadlineNotReached,
+        /// Returned if the native currency could not be transferred.
+        NativeTransferFailed,
+        /// Returned if the token contract refused the transfer.
+        TokenTransferFailed(erc20::Error),
+        /// Returned if no more deal IDs are available.
+        Overflow,
+    }
+
+    /// Specify the escrow result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Escrow {
+        /// Create a new escrow judged by `arbiter` that also accepts the
+        /// `Erc20` token at `token`.
+        #[ink(constructor)]
+        pub fn new(arbiter: AccountId, token: AccountId) -> Self {
+            Self {
+                arbiter,
+                token: ink::env::call::FromAccountId::from_account_id(token),
+                next_id: 0,
+                deals: Mapping::default(),
+            }
+        }
+
+        /// Returns the arbiter of the escrow.
+        #[ink(message)]
+        pub fn arbiter(&self) -> AccountId {
+            self.arbiter
+        }
+
+        /// Returns the deal with the given ID, if any.
+        #[ink(message)]
+        pub fn deal(&self, id: DealId) -> Option<Deal> {
+            self.deals.get(id)
+        }
+
+        /// Holds the native value sent with this call for `seller` until
+        /// `deadline`.
+        #[ink(message, payable)]
+        pub fn deposit(&mut self, seller: AccountId, deadline: Timestamp) -> Result<DealId> {
+            let amount = self.env().transferred_value();
+            self.create_deal(seller, Asset::Native, amount, deadline)
+        }
+
+        /// Takes `amount` tokens from the caller and holds them for `seller`
+        /// until `deadline`.
+        ///
+        /// The caller must first approve the escrow to spend `amount` tokens.
+        #[ink(message)]
+        pub fn deposit_tokens(
+            &mut self,
+            seller: AccountId,
+            amount: Balance,
+            deadline: Timestamp,
+        ) -> Result<DealId> {
+            let id = self.create_deal(seller, Asset::Token, amount, deadline)?;
+            self.token
+                .transfer_from(self.env().caller(), self.env().account_id(), amount)
+                .map_err(Error::TokenTransferFailed)?;
+            Ok(id)
+        }
+
+        /// Pays the deal to the seller. Only the arbiter can call this, and
+        /// only before the deadline.
+        #[ink(message)]
+        pub fn release(&mut self, id: DealId) -> Result<()> {
+            let deal = self.settle(id, DealState::Released)?;
+            self.pay(deal.asset, deal.seller, deal.amount)?;
+
+            // `erc20` also implements `EmitEvent`, so name the impl of this contract.
+            <ink::EnvAccess<Environment> as ink::codegen::EmitEvent<Escrow>>::emit_event(
+                self.env(),
+                Released {
+                    id,
+                    seller: deal.seller,
+                    amount: deal.amount,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Pays the deal back to the buyer. Only the arbiter can call this,
+        /// and only before the deadline.
+        #[ink(message)]
+        pub fn refund(&mut self, id: DealId) -> Result<()> {
+            let deal = self.settle(id, DealState::Refunded)?;
+            self.pay(deal.asset, deal.buyer, deal.amount)?;
+
+            <ink::EnvAccess<Environment> as ink::codegen::EmitEvent<Escrow>>::emit_event(
+                self.env(),
+                Refunded {
+                    id,
+                    buyer: deal.buyer,
+                    amount: deal.amount,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Pays the deal back to the buyer once the deadline has passed
+        /// without the arbiter settling it.
+        ///
+        /// Anyone can call this, so no permission from the arbiter is needed.
+        #[ink(message)]
+        pub fn claim_expired(&mut self, id: DealId) -> Result<()> {
+            let mut deal = self.pending_deal(id)?;
+            if self.env().block_timestamp() <= deal.deadline {
+                return Err(Error::DeadlineNotReached);
+            }
+
+            deal.state = DealState::Expired;
+            self.deals.insert(id, &deal);
+            self.pay(deal.asset, deal.buyer, deal.amount)?;
+
+            <ink::EnvAccess<Environment> as ink::codegen::EmitEvent<Escrow>>::emit_event(
+                self.env(),
+                Expired {
+                    id,
+                    buyer: deal.buyer,
+                    amount: deal.amount,
+                },
+            );
+
+            Ok(())
+        }
+
+        fn create_deal(
+            &mut self,
+            seller: AccountId,
+            asset: Asset,
+            amount: Balance,
+            deadline: Timestamp,
+        ) -> Result<DealId> {
+            if amount == 0 {
+                return Err(Error::ZeroAmount);
+            }
+            if deadline <= self.env().block_timestamp() {
+                return Err(Error::InvalidDeadline);
+            }
+
+            let id = self.next_id;
+            self.next_id = id.checked_add(1).ok_or(Error::Overflow)?;
+
+            let buyer = self.env().caller();
+            let deal = Deal {
+                buyer,
+                seller,
+                asset,
+                amount,
+                deadline,
+                state: DealState::Pending,
+            };
+            self.deals.insert(id, &deal);
+
+            <ink::EnvAccess<Environment> as ink::codegen::EmitEvent<Escrow>>::emit_event(
+                self.env(),
+                Deposited {
+                    id,
+                    buyer,
+                    seller,
+                    asset,
+                    amount,
+                    deadline,
+                },
+            );
+
+            Ok(id)
+        }
+
+        /// Returns the deal if it exists and is still pending.
+        fn pending_deal(&self, id: DealId) -> Result<Deal> {
+            let deal = self.deal(id).ok_or(Error::DealNotFound)?;
+            if deal.state != DealState::Pending {
+                return Err(Error::NotPending);
+            }
+            Ok(deal)
+        }
+
+        /// Moves a pending deal into `state` on behalf of the arbiter.
+        ///
+        /// The new state is stored before any funds leave the escrow.
+        fn settle(&mut self, id: DealId, state: DealState) -> Result<Deal> {
+            if self.env().caller() != self.arbiter {
+                return Err(Error::NotArbiter);
+            }
+            let mut deal = self.pending_deal(id)?;
+            if self.env().block_timestamp() > deal.deadline {
+                return Err(Error::DeadlinePassed);
+            }
+
+            deal.state = state;
+            self.deals.insert(id, &deal);
+            Ok(deal)
+        }
+
+        fn pay(&mut self, asset: Asset, to: AccountId, amount: Balance) -> Result<()> {
+            match asset {
+                Asset::Native => self
+                    .env()
+                    .transfer(to, amount)
+                    .map_err(|_| Error::NativeTransferFailed),
+                Asset::Token => self
+                    .token
+                    .transfer(to, amount)
+                    .map_err(Error::TokenTransferFailed),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        type Event = <Escrow as ink::reflect::ContractEventBase>::Type;
+
+        fn default_accounts() -> ink::env::test::DefaultAccounts<Environment> {
+            ink::env::test::default_accounts::<Environment>()
+        }
+
+        fn alice() -> AccountId {
+            default_accounts().alice
+        }
+
+        fn bob() -> AccountId {
+            default_accounts().bob
+        }
+
+        fn arbiter() -> AccountId {
+            default_accounts().charlie
+        }
+
+        fn token() -> AccountId {
+            default_accounts().django
+        }
+
+        fn set_caller(caller: AccountId) {
+            ink::env::test::set_caller::<Environment>(caller);
+        }
+
+        fn set_block_timestamp(timestamp: Timestamp) {
+            ink::env::test::set_block_timestamp::<Environment>(timestamp);
+        }
+
+        fn balance_of(account: AccountId) -> Balance {
+            ink::env::test::get_account_balance::<Environment>(account)
+                .expect("account has a balance")
+        }
+
+        fn recorded_events() -> Vec<Event> {
+            ink::env::test::recorded_events()
+                .map(|event| {
+                    <Event as scale::Decode>::decode(&mut &event.data[..])
+                        .expect("encountered invalid contract event data buffer")
+                })
+                .collect()
+        }
+
+        fn assert_deposited_event(
+            event: &Event,
+            expected_id: DealId,
+            expected_amount: Balance,
+            expected_deadline: Timestamp,
+        ) {
+            if let Event::Deposited(Deposited {
+                id,
+                buyer,
+                seller,
+                asset,
+                amount,
+                deadline,
+            }) = event
+            {
+                assert_eq!(*id, expected_id, "encountered invalid Deposited.id");
+                assert_eq!(*buyer, alice(), "encountered invalid Deposited.buyer");
+                assert_eq!(*seller, bob(), "encountered invalid Deposited.seller");
+                assert_eq!(*asset, Asset::Native, "encountered invalid Deposited.asset");
+                assert_eq!(
+                    *amount, expected_amount,
+                    "encountered invalid Deposited.amount"
+                );
+                assert_eq!(
+                    *deadline, expected_deadline,
+                    "encountered invalid Deposited.deadline"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected a Deposited event")
+            }
+        }
+
+        fn assert_released_event(event: &Event, expected_id: DealId, expected_amount: Balance) {
+            if let Event::Released(Released { id, seller, amount }) = event {
+                assert_eq!(*id, expected_id, "encountered invalid Released.id");
+                assert_eq!(*seller, bob(), "encountered invalid Released.seller");
+                assert_eq!(
+                    *amount, expected_amount,
+                    "encountered invalid Released.amount"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected a Released event")
+            }
+        }
+
+        fn assert_refunded_event(event: &Event, expected_id: DealId, expected_amount: Balance) {
+            if let Event::Refunded(Refunded { id, buyer, amount }) = event {
+                assert_eq!(*id, expected_id, "encountered invalid Refunded.id");
+                assert_eq!(*buyer, alice(), "encountered invalid Refunded.buyer");
+                assert_eq!(
+                    *amount, expected_amount,
+                    "encountered invalid Refunded.amount"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected a Refunded event")
+            }
+        }
+
+        fn assert_expired_event(event: &Event, expected_id: DealId, expected_amount: Balance) {
+            if let Event::Expired(Expired { id, buyer, amount }) = event {
+                assert_eq!(*id, expected_id, "encountered invalid Expired.id");
+                assert_eq!(*buyer, alice(), "encountered invalid Expired.buyer");
+                assert_eq!(
+                    *amount, expected_amount,
+                    "encountered invalid Expired.amount"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected an Expired event")
+            }
+        }
+
+        /// Creates an escrow deployed at `eve`, so that its balance is kept
+        /// apart from the buyer's.
+        fn new_escrow() -> Escrow {
+            ink::env::test::set_callee::<Environment>(default_accounts().eve);
+            Escrow::new(arbiter(), token())
+        }
+
+        /// Deposits `amount` of native value from `alice` for `bob`.
+        ///
+        /// The off-chain environment does not move the transferred value, so
+        /// this credits the contract with it.
+        fn deposit(contract: &mut Escrow, amount: Balance, deadline: Timestamp) -> Result<DealId> {
+            let contract_id = ink::env::test::callee::<Environment>();
+            ink::env::test::set_account_balance::<Environment>(
+                contract_id,
+                balance_of(contract_id) + amount,
+            );
+            set_caller(alice());
+            ink::env::test::set_value_transferred::<Environment>(amount);
+            contract.deposit(bob(), deadline)
+        }
+
+        #[ink::test]
+        fn deposit_works() {
+            let mut contract = new_escrow();
+            assert_eq!(contract.arbiter(), arbiter());
+            set_block_timestamp(100);
+            assert_eq!(deposit(&mut contract, 50, 1_000), Ok(0));
+            assert_eq!(
+                contract.deal(0),
+                Some(Deal {
+                    buyer: alice(),
+                    seller: bob(),
+                    asset: Asset::Native,
+                    amount: 50,
+                    deadline: 1_000,
+                    state: DealState::Pending,
+                })
+            );
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 1);
+            assert_deposited_event(&emitted_events[0], 0, 50, 1_000);
+        }
+
+        #[ink::test]
+        fn deposit_rejects_invalid_deals() {
+            let mut contract = new_escrow();
+            set_block_timestamp(100);
+            assert_eq!(deposit(&mut contract, 0, 1_000), Err(Error::ZeroAmount));
+            assert_eq!(deposit(&mut contract, 50, 100), Err(Error::InvalidDeadline));
+            assert_eq!(
+                contract.deposit_tokens(bob(), 0, 1_000),
+                Err(Error::ZeroAmount)
+            );
+            assert_eq!(contract.deal(0), None);
+            assert_eq!(recorded_events().len(), 0);
+        }
+
+        #[ink::test]
+        fn release_works() {
+            let mut contract = new_escrow();
+            assert_eq!(deposit(&mut contract, 50, 1_000), Ok(0));
+            let seller_balance = balance_of(bob());
+
+            set_caller(arbiter());
+            assert_eq!(contract.release(0), Ok(()));
+            assert_eq!(balance_of(bob()), seller_balance + 50);
+            assert_eq!(
+                contract.deal(0).map(|deal| deal.state),
+                Some(DealState::Released)
+            );
+
+            // A settled deal cannot be settled again.
+            assert_eq!(contract.release(0), Err(Error::NotPending));
+            assert_eq!(contract.refund(0), Err(Error::NotPending));
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_released_event(&emitted_events[1], 0, 50);
+        }
+
+        #[ink::test]
+        fn refund_works() {
+            let mut contract = new_escrow();
+            assert_eq!(deposit(&mut contract, 50, 1_000), Ok(0));
+            let buyer_balance = balance_of(alice());
+
+            set_caller(arbiter());
+            assert_eq!(contract.refund(0), Ok(()));
+            assert_eq!(balance_of(alice()), buyer_balance + 50);
+            assert_eq!(
+                contract.deal(0).map(|deal| deal.state),
+                Some(DealState::Refunded)
+            );
+            assert_eq!(contract.claim_expired(0), Err(Error::NotPending));
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_refunded_event(&emitted_events[1], 0, 50);
+        }
+
+        #[ink::test]
+        fn only_arbiter_settles() {
+            let mut contract = new_escrow();
+            assert_eq!(deposit(&mut contract, 50, 1_000), Ok(0));
+            assert_eq!(contract.release(0), Err(Error::NotArbiter));
+
+            set_caller(bob());
+            assert_eq!(contract.release(0), Err(Error::NotArbiter));
+            assert_eq!(contract.refund(0), Err(Error::NotArbiter));
+
+            set_caller(arbiter());
+            assert_eq!(contract.release(1), Err(Error::DealNotFound));
+            assert_eq!(
+                contract.deal(0).map(|deal| deal.state),
+                Some(DealState::Pending)
+            );
+            assert_eq!(recorded_events().len(), 1);
+        }
+
+        #[ink::test]
+        fn claim_expired_works() {
+            let mut contract = new_escrow();
+            assert_eq!(deposit(&mut contract, 50, 1_000), Ok(0));
+            let buyer_balance = balance_of(alice());
+
+            set_caller(bob());
+            set_block_timestamp(1_000);
+            assert_eq!(contract.claim_expired(0), Err(Error::DeadlineNotReached));
+
+            // After the deadline the arbiter can no longer settle the deal, and
+            // anyone can return the payment to the buyer.
+            set_block_timestamp(1_001);
+            set_caller(arbiter());
+            assert_eq!(contract.release(0), Err(Error::DeadlinePassed));
+            set_caller(bob());
+            assert_eq!(contract.claim_expired(0), Ok(()));
+            assert_eq!(balance_of(alice()), buyer_balance + 50);
+            assert_eq!(
+                contract.deal(0).map(|deal| deal.state),
+                Some(DealState::Expired)
+            );
+            assert_eq!(contract.claim_expired(0), Err(Error::NotPending));
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_expired_event(&emitted_events[1], 0, 50);
+        }
+
+        #[ink::test]
+        fn deals_are_independent() {
+            let mut contract = new_escrow();
+            assert_eq!(deposit(&mut contract, 50, 1_000), Ok(0));
+            assert_eq!(deposit(&mut contract, 70, 2_000), Ok(1));
+
+            set_caller(arbiter());
+            assert_eq!(contract.release(0), Ok(()));
+            assert_eq!(contract.refund(1), Ok(()));
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 4);
+            assert_deposited_event(&emitted_events[0], 0, 50, 1_000);
+            assert_deposited_event(&emitted_events[1], 1, 70, 2_000);
+            assert_released_event(&emitted_events[2], 0, 50);
+            assert_refunded_event(&emitted_events[3], 1, 70);
+        }
+    }
+
+    /// End-to-end tests of an `Erc20` deal, which unit tests cannot cover
+    /// because the off-chain environment does not run cross-contract calls.
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::build_message;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test(additional_contracts = "../erc20/Cargo.toml")]
+        async fn e2e_token_deal_is_released(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let charlie = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+
+            let constructor = Erc20Ref::new(1_000);
+            let erc20_id = client
+                .instantiate("erc20", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate `erc20` failed")
+                .account_id;
+
+            let constructor = EscrowRef::new(charlie, erc20_id.clone());
+            let escrow_id = client
+                .instantiate("escrow", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate `escrow` failed")
+                .account_id;
+
+            // Alice lets the escrow take 100 tokens and deposits them for bob.
+            let approve = build_message::<Erc20Ref>(erc20_id.clone())
+                .call(|erc20| erc20.approve(escrow_id.clone(), 100));
+            client
+                .call(&ink_e2e::alice(), approve, 0, None)
+                .await
+                .expect("approve failed");
+
+            let deposit = build_message::<EscrowRef>(escrow_id.clone())
+                .call(|escrow| escrow.deposit_tokens(bob, 100, Timestamp::MAX));
+            let id = client
+                .call(&ink_e2e::alice(), deposit, 0, None)
+                .await
+                .expect("deposit failed")
+                .return_value()
+                .expect("deposit returned an error");
+
+            let balance_of = build_message::<Erc20Ref>(erc20_id.clone())
+                .call(|erc20| erc20.balance_of(escrow_id.clone()));
+            let balance = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, 100);
+
+            let release =
+                build_message::<EscrowRef>(escrow_id.clone()).call(|escrow| escrow.release(id));
+            client
+                .call(&ink_e2e::charlie(), release, 0, None)
+                .await
+                .expect("release failed");
+
+            let balance_of =
+                build_message::<Erc20Ref>(erc20_id.clone()).call(|erc20| erc20.balance_of(bob));
+            let balance = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, 100);
+
+            Ok(())
+        }
+    }
+}
@@ -6,6 +6,20 @@ mod incrementer {
     #[ink(storage)]
     pub struct Incrementer {
         value: i32,
+        /// The lowest value `inc` may reach, if any.
+        min_value: Option<i32>,
+        /// The highest value `inc` may reach, if any.
+        max_value: Option<i32>,
+    }
+
+    /// Specify the incrementer error type.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the new value does not fit in an `i32`.
+        Overflow,
+        /// Returned if a value lies outside the configured bounds.
+        OutOfBounds,
     }
 
     impl Incrementer {
@@ -13,14 +27,31 @@ mod incrementer {
         pub fn new(init_value: i32) -> Self {
             Self {
                 value: init_value,
+                min_value: None,
+                max_value: None,
             }
         }
 
         #[ink(constructor)]
         pub fn default() -> Self {
-            Self {
-                value: Default::default(),
-            }
+            Self::new(Default::default())
+        }
+
+        /// Create a new incrementer whose value must stay within the optional
+        /// `min_value` and `max_value` bounds.
+        #[ink(constructor)]
+        pub fn new_with_bounds(
+            init_value: i32,
+            min_value: Option<i32>,
+            max_value: Option<i32>,
+        ) -> Result<Self, Error> {
+            let contract = Self {
+                value: init_value,
+                min_value,
+                max_value,
+            };
+            contract.check_bounds(init_value)?;
+            Ok(contract)
         }
 
         #[ink(message)]
@@ -29,8 +60,20 @@ mod incrementer {
         }
 
         #[ink(message)]
-        pub fn inc(&mut self, by: i32) {
-            self.value += by;
+        pub fn inc(&mut self, by: i32) -> Result<(), Error> {
+            let value = self.value.checked_add(by).ok_or(Error::Overflow)?;
+            self.check_bounds(value)?;
+            self.value = value;
+            Ok(())
+        }
+
+        fn check_bounds(&self, value: i32) -> Result<(), Error> {
+            let below_min = self.min_value.is_some_and(|min| value < min);
+            let above_max = self.max_value.is_some_and(|max| value > max);
+            if below_min || above_max {
+                return Err(Error::OutOfBounds);
+            }
+            Ok(())
         }
     }
 
@@ -48,10 +91,40 @@ mod incrementer {
         fn it_works() {
             let mut contract = Incrementer::new(42);
             assert_eq!(contract.get(), 42);
-            contract.inc(5);
+            assert_eq!(contract.inc(5), Ok(()));
             assert_eq!(contract.get(), 47);
-            contract.inc(-50);
+            assert_eq!(contract.inc(-50), Ok(()));
             assert_eq!(contract.get(), -3);
         }
+
+        #[ink::test]
+        fn inc_overflow_fails() {
+            let mut contract = Incrementer::new(i32::MAX);
+            assert_eq!(contract.inc(1), Err(Error::Overflow));
+            assert_eq!(contract.get(), i32::MAX);
+            assert_eq!(contract.inc(0), Ok(()));
+
+            let mut contract = Incrementer::new(i32::MIN);
+            assert_eq!(contract.inc(-1), Err(Error::Overflow));
+            assert_eq!(contract.get(), i32::MIN);
+            assert_eq!(contract.inc(i32::MAX), Ok(()));
+            assert_eq!(contract.get(), -1);
+        }
+
+        #[ink::test]
+        fn bounds_work() {
+            let mut contract = Incrementer::new_with_bounds(0, Some(-10), Some(10)).unwrap();
+            assert_eq!(contract.inc(10), Ok(()));
+            assert_eq!(contract.inc(1), Err(Error::OutOfBounds));
+            assert_eq!(contract.get(), 10);
+            assert_eq!(contract.inc(-20), Ok(()));
+            assert_eq!(contract.inc(-1), Err(Error::OutOfBounds));
+            assert_eq!(contract.get(), -10);
+
+            assert_eq!(
+                Incrementer::new_with_bounds(11, None, Some(10)).err(),
+                Some(Error::OutOfBounds)
+            );
+        }
     }
 }
@@ -0,0 +1,133 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// An incrementer that instantiates and holds its own `Erc20` token.
+///
+/// This contract calls the token through `erc20::Erc20Ref`, so the contract in
+/// `erc20-final.rs` must be added as a dependency with the `ink-as-dependency`
+/// feature enabled.
+#[ink::contract]
+mod incrementer {
+    use erc20::Erc20Ref;
+
+    #[ink(storage)]
+    pub struct Incrementer {
+        value: i32,
+        /// The token instantiated by this contract, which holds its whole supply.
+        token: Erc20Ref,
+    }
+
+    impl Incrementer {
+        /// Instantiates a new `Erc20` from `erc20_code_hash` with `total_supply`
+        /// tokens, all of which are owned by this contract.
+        #[ink(constructor)]
+        pub fn new(init_value: i32, erc20_code_hash: Hash, total_supply: Balance) -> Self {
+            let token = Erc20Ref::new(total_supply)
+                .code_hash(erc20_code_hash)
+                .endowment(0)
+                .salt_bytes([0xde, 0xad, 0xbe, 0xef])
+                .instantiate();
+
+            Self {
+                value: init_value,
+                token,
+            }
+        }
+
+        #[ink(message)]
+        pub fn get(&self) -> i32 {
+            self.value
+        }
+
+        #[ink(message)]
+        pub fn inc(&mut self, by: i32) {
+            self.value += by;
+        }
+
+        /// Returns the account of the token held by this contract.
+        #[ink(message)]
+        pub fn token(&self) -> AccountId {
+            ink::ToAccountId::to_account_id(&self.token)
+        }
+
+        /// Transfers `value` of this contract's tokens to `to`.
+        ///
+        /// Anyone can call this message, so a real contract would restrict it.
+        #[ink(message)]
+        pub fn transfer(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+        ) -> core::result::Result<(), erc20::Error> {
+            self.token.transfer(to, value)
+        }
+
+        /// Returns the token balance of `owner`.
+        #[ink(message)]
+        pub fn balance_of(&self, owner: AccountId) -> Balance {
+            self.token.balance_of(owner)
+        }
+    }
+
+    /// End-to-end tests that run against a local `substrate-contracts-node`.
+    ///
+    /// These require `ink_e2e` as a dev-dependency and an `e2e-tests = []`
+    /// feature in `Cargo.toml`. Run them with `cargo test --features e2e-tests`.
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::build_message;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test(additional_contracts = "../erc20/Cargo.toml")]
+        async fn e2e_forwards_to_erc20(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let erc20_code_hash = client
+                .upload("erc20", &ink_e2e::alice(), None)
+                .await
+                .expect("uploading `erc20` failed")
+                .code_hash;
+
+            let constructor = IncrementerRef::new(0, erc20_code_hash, 1_000);
+            let contract_id = client
+                .instantiate("incrementer", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+
+            let transfer = build_message::<IncrementerRef>(contract_id.clone())
+                .call(|incrementer| incrementer.transfer(bob, 100));
+            client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+
+            let balance_of = build_message::<IncrementerRef>(contract_id.clone())
+                .call(|incrementer| incrementer.balance_of(contract_id));
+            let balance = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, 900);
+
+            // The balance is also visible when calling the token directly.
+            let token = build_message::<IncrementerRef>(contract_id.clone())
+                .call(|incrementer| incrementer.token());
+            let token_id = client
+                .call_dry_run(&ink_e2e::alice(), &token, 0, None)
+                .await
+                .return_value();
+
+            let balance_of =
+                build_message::<Erc20Ref>(token_id).call(|erc20| erc20.balance_of(bob));
+            let balance = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, 100);
+
+            Ok(())
+        }
+    }
+}
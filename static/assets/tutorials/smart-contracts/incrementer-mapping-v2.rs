@@ -0,0 +1,362 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::incrementer::{Incrementer, IncrementerRef};
+
+/// The second version of the `Incrementer` in `incrementer-mapping.rs`.
+///
+/// It adds `inc_count` to the storage, so live instances must be upgraded
+/// through the contract in `incrementer-migration.rs`. The storage struct and
+/// the `my_map` field keep their names so that `my_map` entries stay under
+/// the same storage keys.
+#[ink::contract]
+mod incrementer {
+    use ink::storage::Mapping;
+
+    #[ink(storage)]
+    pub struct Incrementer {
+        value: i32,
+        my_map: Mapping<AccountId, i32>,
+        /// The account allowed to upgrade the contract code.
+        owner: AccountId,
+        /// The lowest value `inc` and `inc_mine` may reach, if any.
+        min_value: Option<i32>,
+        /// The highest value `inc` and `inc_mine` may reach, if any.
+        max_value: Option<i32>,
+        /// The number of times `inc` was called since the upgrade.
+        inc_count: u64,
+        /// The roles held by each account.
+        roles: Mapping<(Role, AccountId), ()>,
+    }
+
+    #[ink(event)]
+    pub struct Incremented {
+        #[ink(topic)]
+        who: AccountId,
+        by: i32,
+        new_value: i32,
+    }
+
+    #[ink(event)]
+    pub struct Removed {
+        #[ink(topic)]
+        who: AccountId,
+    }
+
+    /// The roles that can be granted to accounts.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Role {
+        /// May grant and revoke roles.
+        Admin,
+        /// May call `inc` on the shared value.
+        Incrementer,
+    }
+
+    /// Specify the incrementer error type.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not the contract owner.
+        NotOwner,
+        /// Returned if the new value does not fit in an `i32`.
+        Overflow,
+        /// Returned if a value lies outside the configured bounds.
+        OutOfBounds,
+        /// Returned if the caller does not hold the role a message requires.
+        MissingRole(Role),
+        /// Returned if the contract code could not be replaced.
+        SetCodeHashFailed,
+    }
+
+    impl Incrementer {
+        #[ink(constructor)]
+        pub fn new(init_value: i32) -> Self {
+            let mut my_map = Mapping::default();
+            let caller = Self::env().caller();
+            my_map.insert(&caller, &0);
+
+            let mut contract = Self {
+                value: init_value,
+                my_map,
+                owner: caller,
+                min_value: None,
+                max_value: None,
+                inc_count: 0,
+                roles: Mapping::default(),
+            };
+            contract.roles.insert((Role::Admin, caller), &());
+            contract.roles.insert((Role::Incrementer, caller), &());
+            contract
+        }
+
+        /// Create a new incrementer whose values must stay within the optional
+        /// `min_value` and `max_value` bounds.
+        #[ink(constructor)]
+        pub fn new_with_bounds(
+            init_value: i32,
+            min_value: Option<i32>,
+            max_value: Option<i32>,
+        ) -> Result<Self, Error> {
+            let mut contract = Self::new(init_value);
+            contract.min_value = min_value;
+            contract.max_value = max_value;
+            contract.check_bounds(init_value)?;
+            Ok(contract)
+        }
+
+        #[ink(message)]
+        pub fn get(&self) -> i32 {
+            self.value
+        }
+
+        #[ink(message)]
+        pub fn inc(&mut self, by: i32) -> Result<(), Error> {
+            self.ensure_role(Role::Incrementer)?;
+            let value = self.value.checked_add(by).ok_or(Error::Overflow)?;
+            self.check_bounds(value)?;
+            self.value = value;
+            self.inc_count += 1;
+
+            self.env().emit_event(Incremented {
+                who: self.env().caller(),
+                by,
+                new_value: value,
+            });
+
+            Ok(())
+        }
+
+        /// Returns the number of times `inc` was called.
+        #[ink(message)]
+        pub fn inc_count(&self) -> u64 {
+            self.inc_count
+        }
+
+        #[ink(message)]
+        pub fn get_mine(&self) -> i32 {
+            let caller = self.env().caller();
+            self.my_map.get(&caller).unwrap_or_default()
+        }
+
+        #[ink(message)]
+        pub fn inc_mine(&mut self, by: i32) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let my_value = self.get_mine().checked_add(by).ok_or(Error::Overflow)?;
+            self.check_bounds(my_value)?;
+            self.my_map.insert(caller, &my_value);
+
+            self.env().emit_event(Incremented {
+                who: caller,
+                by,
+                new_value: my_value,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn remove_mine(&mut self) {
+            let caller = self.env().caller();
+            self.my_map.remove(&caller);
+
+            self.env().emit_event(Removed { who: caller });
+        }
+
+        /// Replaces the code of this contract with the code at `code_hash`.
+        ///
+        /// Storage is kept as it is, so the new code must either use the same
+        /// storage layout or be a migration contract that converts it.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: [u8; 32]) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            ink::env::set_code_hash(&code_hash).map_err(|_| Error::SetCodeHashFailed)?;
+
+            Ok(())
+        }
+
+        /// Returns `true` if `account` holds `role`.
+        #[ink(message)]
+        pub fn has_role(&self, role: Role, account: AccountId) -> bool {
+            self.roles.contains((role, account))
+        }
+
+        /// Grants `role` to `account`. Only admins may call this.
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: Role, account: AccountId) -> Result<(), Error> {
+            self.ensure_role(Role::Admin)?;
+            self.roles.insert((role, account), &());
+            Ok(())
+        }
+
+        /// Revokes `role` from `account`. Only admins may call this.
+        #[ink(message)]
+        pub fn revoke_role(&mut self, role: Role, account: AccountId) -> Result<(), Error> {
+            self.ensure_role(Role::Admin)?;
+            self.roles.remove((role, account));
+            Ok(())
+        }
+
+        fn ensure_role(&self, role: Role) -> Result<(), Error> {
+            if !self.has_role(role, self.env().caller()) {
+                return Err(Error::MissingRole(role));
+            }
+            Ok(())
+        }
+
+        fn check_bounds(&self, value: i32) -> Result<(), Error> {
+            let below_min = self.min_value.is_some_and(|min| value < min);
+            let above_max = self.max_value.is_some_and(|max| value > max);
+            if below_min || above_max {
+                return Err(Error::OutOfBounds);
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        type Event = <Incrementer as ink::reflect::ContractEventBase>::Type;
+
+        fn recorded_events() -> Vec<Event> {
+            ink::env::test::recorded_events()
+                .map(|event| {
+                    <Event as scale::Decode>::decode(&mut &event.data[..])
+                        .expect("encountered invalid contract event data buffer")
+                })
+                .collect()
+        }
+
+        fn assert_incremented_event(
+            event: &Event,
+            expected_who: AccountId,
+            expected_by: i32,
+            expected_new_value: i32,
+        ) {
+            if let Event::Incremented(Incremented { who, by, new_value }) = event {
+                assert_eq!(*who, expected_who, "encountered invalid Incremented.who");
+                assert_eq!(*by, expected_by, "encountered invalid Incremented.by");
+                assert_eq!(
+                    *new_value, expected_new_value,
+                    "encountered invalid Incremented.new_value"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected an Incremented event")
+            }
+        }
+
+        fn assert_removed_event(event: &Event, expected_who: AccountId) {
+            if let Event::Removed(Removed { who }) = event {
+                assert_eq!(*who, expected_who, "encountered invalid Removed.who");
+            } else {
+                panic!("encountered unexpected event kind: expected a Removed event")
+            }
+        }
+
+        fn alice() -> AccountId {
+            ink::env::test::default_accounts::<Environment>().alice
+        }
+
+        fn bob() -> AccountId {
+            ink::env::test::default_accounts::<Environment>().bob
+        }
+
+        fn set_caller(caller: AccountId) {
+            ink::env::test::set_caller::<Environment>(caller);
+        }
+
+        #[ink::test]
+        fn inc_count_works() {
+            let mut contract = Incrementer::new(42);
+            assert_eq!(contract.inc_count(), 0);
+            assert_eq!(contract.inc(5), Ok(()));
+            assert_eq!(contract.inc(-50), Ok(()));
+            assert_eq!(contract.inc(i32::MIN), Err(Error::Overflow));
+            assert_eq!(contract.get(), -3);
+            assert_eq!(contract.inc_count(), 2);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_incremented_event(&emitted_events[0], alice(), 5, 47);
+            assert_incremented_event(&emitted_events[1], alice(), -50, -3);
+        }
+
+        #[ink::test]
+        fn my_map_works() {
+            let mut contract = Incrementer::new(11);
+            assert_eq!(contract.get_mine(), 0);
+            assert_eq!(contract.inc_mine(5), Ok(()));
+            assert_eq!(contract.get_mine(), 5);
+            contract.remove_mine();
+            assert_eq!(contract.get_mine(), 0);
+            assert_eq!(contract.inc_count(), 0);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_incremented_event(&emitted_events[0], alice(), 5, 5);
+            assert_removed_event(&emitted_events[1], alice());
+        }
+
+        #[ink::test]
+        fn set_code_requires_owner() {
+            let mut contract = Incrementer::new(11);
+            set_caller(bob());
+            assert_eq!(contract.set_code([0x01; 32]), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn inc_requires_incrementer_role() {
+            let mut contract = Incrementer::new(42);
+            assert!(contract.has_role(Role::Admin, alice()));
+            assert!(contract.has_role(Role::Incrementer, alice()));
+            assert!(!contract.has_role(Role::Incrementer, bob()));
+
+            set_caller(bob());
+            assert_eq!(contract.inc(1), Err(Error::MissingRole(Role::Incrementer)));
+            assert_eq!(contract.get(), 42);
+
+            // Anyone may still change their own value.
+            assert_eq!(contract.inc_mine(1), Ok(()));
+            assert_eq!(contract.get_mine(), 1);
+        }
+
+        #[ink::test]
+        fn grant_and_revoke_role_work() {
+            let mut contract = Incrementer::new(42);
+            assert_eq!(contract.grant_role(Role::Incrementer, bob()), Ok(()));
+            assert!(contract.has_role(Role::Incrementer, bob()));
+
+            set_caller(bob());
+            assert_eq!(contract.inc(1), Ok(()));
+            assert_eq!(contract.get(), 43);
+
+            set_caller(alice());
+            assert_eq!(contract.revoke_role(Role::Incrementer, bob()), Ok(()));
+            assert!(!contract.has_role(Role::Incrementer, bob()));
+
+            set_caller(bob());
+            assert_eq!(contract.inc(1), Err(Error::MissingRole(Role::Incrementer)));
+            assert_eq!(contract.get(), 43);
+        }
+
+        #[ink::test]
+        fn only_admins_manage_roles() {
+            let mut contract = Incrementer::new(42);
+            set_caller(bob());
+            assert_eq!(
+                contract.grant_role(Role::Incrementer, bob()),
+                Err(Error::MissingRole(Role::Admin))
+            );
+            assert_eq!(
+                contract.revoke_role(Role::Admin, alice()),
+                Err(Error::MissingRole(Role::Admin))
+            );
+            assert!(!contract.has_role(Role::Incrementer, bob()));
+            assert!(contract.has_role(Role::Admin, alice()));
+        }
+    }
+}
@@ -8,6 +8,54 @@ mod incrementer {
     pub struct Incrementer {
         value: i32,
         my_map: Mapping<AccountId, i32>,
+        /// The account allowed to upgrade the contract code.
+        owner: AccountId,
+        /// The lowest value `inc` and `inc_mine` may reach, if any.
+        min_value: Option<i32>,
+        /// The highest value `inc` and `inc_mine` may reach, if any.
+        max_value: Option<i32>,
+        /// The roles held by each account.
+        roles: Mapping<(Role, AccountId), ()>,
+    }
+
+    #[ink(event)]
+    pub struct Incremented {
+        #[ink(topic)]
+        who: AccountId,
+        by: i32,
+        new_value: i32,
+    }
+
+    #[ink(event)]
+    pub struct Removed {
+        #[ink(topic)]
+        who: AccountId,
+    }
+
+    /// The roles that can be granted to accounts.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Role {
+        /// May grant and revoke roles.
+        Admin,
+        /// May call `inc` on the shared value.
+        Incrementer,
+    }
+
+    /// Specify the incrementer error type.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not the contract owner.
+        NotOwner,
+        /// Returned if the new value does not fit in an `i32`.
+        Overflow,
+        /// Returned if a value lies outside the configured bounds.
+        OutOfBounds,
+        /// Returned if the caller does not hold the role a message requires.
+        MissingRole(Role),
+        /// Returned if the contract code could not be replaced.
+        SetCodeHashFailed,
     }
 
     impl Incrementer {
@@ -17,10 +65,32 @@ mod incrementer {
             let caller = Self::env().caller();
             my_map.insert(&caller, &0);
 
-            Self {
+            let mut contract = Self {
                 value: init_value,
                 my_map,
-            }
+                owner: caller,
+                min_value: None,
+                max_value: None,
+                roles: Mapping::default(),
+            };
+            contract.roles.insert((Role::Admin, caller), &());
+            contract.roles.insert((Role::Incrementer, caller), &());
+            contract
+        }
+
+        /// Create a new incrementer whose values must stay within the optional
+        /// `min_value` and `max_value` bounds.
+        #[ink(constructor)]
+        pub fn new_with_bounds(
+            init_value: i32,
+            min_value: Option<i32>,
+            max_value: Option<i32>,
+        ) -> Result<Self, Error> {
+            let mut contract = Self::new(init_value);
+            contract.min_value = min_value;
+            contract.max_value = max_value;
+            contract.check_bounds(init_value)?;
+            Ok(contract)
         }
 
         #[ink(message)]
@@ -29,8 +99,19 @@ mod incrementer {
         }
 
         #[ink(message)]
-        pub fn inc(&mut self, by: i32) {
-            self.value += by;
+        pub fn inc(&mut self, by: i32) -> Result<(), Error> {
+            self.ensure_role(Role::Incrementer)?;
+            let value = self.value.checked_add(by).ok_or(Error::Overflow)?;
+            self.check_bounds(value)?;
+            self.value = value;
+
+            self.env().emit_event(Incremented {
+                who: self.env().caller(),
+                by,
+                new_value: value,
+            });
+
+            Ok(())
         }
 
         #[ink(message)]
@@ -40,31 +121,152 @@ mod incrementer {
         }
 
         #[ink(message)]
-        pub fn inc_mine(&mut self, by: i32) {
+        pub fn inc_mine(&mut self, by: i32) -> Result<(), Error> {
             let caller = self.env().caller();
-            let my_value = self.get_mine();
-            self.my_map.insert(caller, &(my_value + by));
+            let my_value = self.get_mine().checked_add(by).ok_or(Error::Overflow)?;
+            self.check_bounds(my_value)?;
+            self.my_map.insert(caller, &my_value);
+
+            self.env().emit_event(Incremented {
+                who: caller,
+                by,
+                new_value: my_value,
+            });
+
+            Ok(())
         }
 
         #[ink(message)]
-        pub fn remove_mine(&self) {
+        pub fn remove_mine(&mut self) {
             let caller = self.env().caller();
-            self.my_map.remove(&caller)
+            self.my_map.remove(&caller);
+
+            self.env().emit_event(Removed { who: caller });
+        }
+
+        /// Replaces the code of this contract with the code at `code_hash`.
+        ///
+        /// Storage is kept as it is, so the new code must either use the same
+        /// storage layout or be a migration contract that converts it.
+        #[ink(message)]
+        pub fn set_code(&mut self, code_hash: [u8; 32]) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            ink::env::set_code_hash(&code_hash).map_err(|_| Error::SetCodeHashFailed)?;
+
+            Ok(())
+        }
+
+        /// Returns `true` if `account` holds `role`.
+        #[ink(message)]
+        pub fn has_role(&self, role: Role, account: AccountId) -> bool {
+            self.roles.contains((role, account))
+        }
+
+        /// Grants `role` to `account`. Only admins may call this.
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: Role, account: AccountId) -> Result<(), Error> {
+            self.ensure_role(Role::Admin)?;
+            self.roles.insert((role, account), &());
+            Ok(())
+        }
+
+        /// Revokes `role` from `account`. Only admins may call this.
+        #[ink(message)]
+        pub fn revoke_role(&mut self, role: Role, account: AccountId) -> Result<(), Error> {
+            self.ensure_role(Role::Admin)?;
+            self.roles.remove((role, account));
+            Ok(())
+        }
+
+        fn ensure_role(&self, role: Role) -> Result<(), Error> {
+            if !self.has_role(role, self.env().caller()) {
+                return Err(Error::MissingRole(role));
+            }
+            Ok(())
+        }
+
+        fn check_bounds(&self, value: i32) -> Result<(), Error> {
+            let below_min = self.min_value.is_some_and(|min| value < min);
+            let above_max = self.max_value.is_some_and(|max| value > max);
+            if below_min || above_max {
+                return Err(Error::OutOfBounds);
+            }
+            Ok(())
         }
     }
 
+    /// Unit tests. `values_follow_a_model` is a model-based property test
+    /// that needs `proptest` as a dev-dependency.
     #[cfg(test)]
     mod tests {
         use super::*;
+        use proptest::prelude::*;
+
+        type Event = <Incrementer as ink::reflect::ContractEventBase>::Type;
+
+        fn recorded_events() -> Vec<Event> {
+            ink::env::test::recorded_events()
+                .map(|event| {
+                    <Event as scale::Decode>::decode(&mut &event.data[..])
+                        .expect("encountered invalid contract event data buffer")
+                })
+                .collect()
+        }
+
+        fn assert_incremented_event(
+            event: &Event,
+            expected_who: AccountId,
+            expected_by: i32,
+            expected_new_value: i32,
+        ) {
+            if let Event::Incremented(Incremented { who, by, new_value }) = event {
+                assert_eq!(*who, expected_who, "encountered invalid Incremented.who");
+                assert_eq!(*by, expected_by, "encountered invalid Incremented.by");
+                assert_eq!(
+                    *new_value, expected_new_value,
+                    "encountered invalid Incremented.new_value"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected an Incremented event")
+            }
+        }
+
+        fn assert_removed_event(event: &Event, expected_who: AccountId) {
+            if let Event::Removed(Removed { who }) = event {
+                assert_eq!(*who, expected_who, "encountered invalid Removed.who");
+            } else {
+                panic!("encountered unexpected event kind: expected a Removed event")
+            }
+        }
+
+        fn alice() -> AccountId {
+            ink::env::test::default_accounts::<Environment>().alice
+        }
+
+        fn bob() -> AccountId {
+            ink::env::test::default_accounts::<Environment>().bob
+        }
+
+        fn set_caller(caller: AccountId) {
+            ink::env::test::set_caller::<Environment>(caller);
+        }
 
         #[ink::test]
         fn it_works() {
             let mut contract = Incrementer::new(42);
             assert_eq!(contract.get(), 42);
-            contract.inc(5);
+            assert_eq!(contract.inc(5), Ok(()));
             assert_eq!(contract.get(), 47);
-            contract.inc(-50);
+            assert_eq!(contract.inc(-50), Ok(()));
             assert_eq!(contract.get(), -3);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_incremented_event(&emitted_events[0], alice(), 5, 47);
+            assert_incremented_event(&emitted_events[1], alice(), -50, -3);
         }
 
         #[ink::test]
@@ -72,9 +274,9 @@ mod incrementer {
             let mut contract = Incrementer::new(11);
             assert_eq!(contract.get(), 11);
             assert_eq!(contract.get_mine(), 0);
-            contract.inc_mine(5);
+            assert_eq!(contract.inc_mine(5), Ok(()));
             assert_eq!(contract.get_mine(), 5);
-            contract.inc_mine(10);
+            assert_eq!(contract.inc_mine(10), Ok(()));
             assert_eq!(contract.get_mine(), 15);
         }
 
@@ -82,20 +284,362 @@ mod incrementer {
         fn inc_mine_works() {
             let mut contract = Incrementer::new(11);
             assert_eq!(contract.get_mine(), 0);
-            contract.inc_mine(5);
+            assert_eq!(contract.inc_mine(5), Ok(()));
             assert_eq!(contract.get_mine(), 5);
-            contract.inc_mine(5);
+            assert_eq!(contract.inc_mine(5), Ok(()));
             assert_eq!(contract.get_mine(), 10);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_incremented_event(&emitted_events[0], alice(), 5, 5);
+            assert_incremented_event(&emitted_events[1], alice(), 5, 10);
         }
 
         #[ink::test]
         fn remove_mine_works() {
             let mut contract = Incrementer::new(11);
             assert_eq!(contract.get_mine(), 0);
-            contract.inc_mine(5);
+            assert_eq!(contract.inc_mine(5), Ok(()));
             assert_eq!(contract.get_mine(), 5);
             contract.remove_mine();
             assert_eq!(contract.get_mine(), 0);
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 2);
+            assert_incremented_event(&emitted_events[0], alice(), 5, 5);
+            assert_removed_event(&emitted_events[1], alice());
+        }
+
+        #[ink::test]
+        fn set_code_requires_owner() {
+            let mut contract = Incrementer::new(11);
+            set_caller(bob());
+            assert_eq!(contract.set_code([0x01; 32]), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn inc_overflow_fails() {
+            let mut contract = Incrementer::new(i32::MAX);
+            assert_eq!(contract.inc(1), Err(Error::Overflow));
+            assert_eq!(contract.get(), i32::MAX);
+
+            let mut contract = Incrementer::new(i32::MIN);
+            assert_eq!(contract.inc(-1), Err(Error::Overflow));
+            assert_eq!(contract.get(), i32::MIN);
+
+            // Failed increments do not emit events.
+            assert!(recorded_events().is_empty());
+        }
+
+        #[ink::test]
+        fn inc_mine_overflow_fails() {
+            let mut contract = Incrementer::new(0);
+            assert_eq!(contract.inc_mine(i32::MAX), Ok(()));
+            assert_eq!(contract.inc_mine(1), Err(Error::Overflow));
+            assert_eq!(contract.get_mine(), i32::MAX);
+
+            assert_eq!(contract.inc_mine(i32::MIN), Ok(()));
+            assert_eq!(contract.get_mine(), -1);
+            assert_eq!(contract.inc_mine(i32::MIN + 1), Ok(()));
+            assert_eq!(contract.get_mine(), i32::MIN);
+            assert_eq!(contract.inc_mine(-1), Err(Error::Overflow));
+            assert_eq!(contract.get_mine(), i32::MIN);
+        }
+
+        #[ink::test]
+        fn bounds_work() {
+            let mut contract = Incrementer::new_with_bounds(0, Some(-10), Some(10)).unwrap();
+            assert_eq!(contract.inc(10), Ok(()));
+            assert_eq!(contract.inc(1), Err(Error::OutOfBounds));
+            assert_eq!(contract.get(), 10);
+            assert_eq!(contract.inc_mine(-10), Ok(()));
+            assert_eq!(contract.inc_mine(-1), Err(Error::OutOfBounds));
+            assert_eq!(contract.get_mine(), -10);
+
+            assert_eq!(
+                Incrementer::new_with_bounds(11, None, Some(10)).err(),
+                Some(Error::OutOfBounds)
+            );
+        }
+
+        #[ink::test]
+        fn inc_requires_incrementer_role() {
+            let mut contract = Incrementer::new(42);
+            assert!(contract.has_role(Role::Admin, alice()));
+            assert!(contract.has_role(Role::Incrementer, alice()));
+            assert!(!contract.has_role(Role::Incrementer, bob()));
+
+            set_caller(bob());
+            assert_eq!(contract.inc(1), Err(Error::MissingRole(Role::Incrementer)));
+            assert_eq!(contract.get(), 42);
+
+            // Anyone may still change their own value.
+            assert_eq!(contract.inc_mine(1), Ok(()));
+            assert_eq!(contract.get_mine(), 1);
+        }
+
+        #[ink::test]
+        fn grant_and_revoke_role_work() {
+            let mut contract = Incrementer::new(42);
+            assert_eq!(contract.grant_role(Role::Incrementer, bob()), Ok(()));
+            assert!(contract.has_role(Role::Incrementer, bob()));
+
+            set_caller(bob());
+            assert_eq!(contract.inc(1), Ok(()));
+            assert_eq!(contract.get(), 43);
+
+            set_caller(alice());
+            assert_eq!(contract.revoke_role(Role::Incrementer, bob()), Ok(()));
+            assert!(!contract.has_role(Role::Incrementer, bob()));
+
+            set_caller(bob());
+            assert_eq!(contract.inc(1), Err(Error::MissingRole(Role::Incrementer)));
+            assert_eq!(contract.get(), 43);
+        }
+
+        #[ink::test]
+        fn only_admins_manage_roles() {
+            let mut contract = Incrementer::new(42);
+            set_caller(bob());
+            assert_eq!(
+                contract.grant_role(Role::Incrementer, bob()),
+                Err(Error::MissingRole(Role::Admin))
+            );
+            assert_eq!(
+                contract.revoke_role(Role::Admin, alice()),
+                Err(Error::MissingRole(Role::Admin))
+            );
+            assert!(!contract.has_role(Role::Incrementer, bob()));
+            assert!(contract.has_role(Role::Admin, alice()));
+        }
+
+        /// A message called by one of the `DefaultAccounts`. Accounts are
+        /// referred to by their index in `accounts()`.
+        #[derive(Debug, Clone)]
+        enum Operation {
+            Inc { caller: usize, by: i32 },
+            IncMine { caller: usize, by: i32 },
+            RemoveMine { caller: usize },
+            GrantIncrementer { account: usize },
+        }
+
+        fn accounts() -> [AccountId; 6] {
+            let accounts = ink::env::test::default_accounts::<Environment>();
+            [
+                accounts.alice,
+                accounts.bob,
+                accounts.charlie,
+                accounts.django,
+                accounts.eve,
+                accounts.frank,
+            ]
+        }
+
+        fn operation() -> impl Strategy<Value = Operation> {
+            let account = 0..6usize;
+            // Mostly small steps that hit the bounds, with some that overflow.
+            let by = prop_oneof![4 => -600..=600i32, 1 => any::<i32>()];
+            prop_oneof![
+                (account.clone(), by.clone())
+                    .prop_map(|(caller, by)| Operation::Inc { caller, by }),
+                (account.clone(), by).prop_map(|(caller, by)| Operation::IncMine { caller, by }),
+                account
+                    .clone()
+                    .prop_map(|caller| Operation::RemoveMine { caller }),
+                account.prop_map(|account| Operation::GrantIncrementer { account }),
+            ]
+        }
+
+        /// Returns the result `inc` or `inc_mine` must have when adding `by` to
+        /// `value` within `min_value` and `max_value`.
+        fn expected_value(
+            value: i32,
+            by: i32,
+            min_value: i32,
+            max_value: i32,
+        ) -> Result<i32, Error> {
+            let value = value.checked_add(by).ok_or(Error::Overflow)?;
+            if value < min_value || value > max_value {
+                return Err(Error::OutOfBounds);
+            }
+            Ok(value)
+        }
+
+        proptest! {
+            #[test]
+            fn values_follow_a_model(
+                min_value in -1_000..=0i32,
+                max_value in 0..=1_000i32,
+                operations in prop::collection::vec(operation(), 1..50),
+            ) {
+                ink::env::test::run_test::<Environment, _>(|_| {
+                    let accounts = accounts();
+                    let mut contract =
+                        Incrementer::new_with_bounds(0, Some(min_value), Some(max_value))
+                            .expect("zero lies within the bounds");
+
+                    // Only the instantiating account may call `inc` at first.
+                    let mut value = 0;
+                    let mut mine = [0; 6];
+                    let mut incrementers = [true, false, false, false, false, false];
+
+                    for operation in &operations {
+                        match *operation {
+                            Operation::Inc { caller, by } => {
+                                set_caller(accounts[caller]);
+                                let expected = if incrementers[caller] {
+                                    expected_value(value, by, min_value, max_value)
+                                } else {
+                                    Err(Error::MissingRole(Role::Incrementer))
+                                };
+                                match expected {
+                                    Ok(new_value) => {
+                                        assert_eq!(contract.inc(by), Ok(()));
+                                        value = new_value;
+                                    }
+                                    Err(err) => assert_eq!(contract.inc(by), Err(err)),
+                                }
+                            }
+                            Operation::IncMine { caller, by } => {
+                                set_caller(accounts[caller]);
+                                match expected_value(mine[caller], by, min_value, max_value) {
+                                    Ok(new_value) => {
+                                        assert_eq!(contract.inc_mine(by), Ok(()));
+                                        mine[caller] = new_value;
+                                    }
+                                    Err(err) => assert_eq!(contract.inc_mine(by), Err(err)),
+                                }
+                            }
+                            Operation::RemoveMine { caller } => {
+                                set_caller(accounts[caller]);
+                                contract.remove_mine();
+                                mine[caller] = 0;
+                            }
+                            Operation::GrantIncrementer { account } => {
+                                set_caller(alice());
+                                assert_eq!(
+                                    contract.grant_role(Role::Incrementer, accounts[account]),
+                                    Ok(())
+                                );
+                                incrementers[account] = true;
+                            }
+                        }
+
+                        assert_eq!(contract.get(), value);
+                        assert!(min_value <= value && value <= max_value);
+                        for (index, account) in accounts.iter().enumerate() {
+                            set_caller(*account);
+                            assert_eq!(contract.get_mine(), mine[index]);
+                            assert_eq!(
+                                contract.has_role(Role::Incrementer, *account),
+                                incrementers[index]
+                            );
+                        }
+                    }
+                    Ok(())
+                })
+                .unwrap();
+            }
+        }
+    }
+
+    /// End-to-end tests that run against a local `substrate-contracts-node`.
+    ///
+    /// These require `ink_e2e` as a dev-dependency and an `e2e-tests = []`
+    /// feature in `Cargo.toml`, as well as the contracts in
+    /// `incrementer-migration.rs` and `incrementer-mapping-v2.rs` as
+    /// dev-dependencies with the `ink-as-dependency` feature enabled.
+    /// Run them with `cargo test --features e2e-tests`.
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use incrementer_migration::MigrationRef;
+        use incrementer_v2::IncrementerRef as IncrementerV2Ref;
+        use ink_e2e::build_message;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test(
+            additional_contracts = "../incrementer-migration/Cargo.toml ../incrementer-v2/Cargo.toml"
+        )]
+        async fn e2e_upgrade_keeps_values(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let constructor = IncrementerRef::new(42);
+            let contract_id = client
+                .instantiate("incrementer", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate failed")
+                .account_id;
+
+            let inc = build_message::<IncrementerRef>(contract_id.clone())
+                .call(|incrementer| incrementer.inc(5));
+            client
+                .call(&ink_e2e::alice(), inc, 0, None)
+                .await
+                .expect("inc failed");
+            let inc_mine = build_message::<IncrementerRef>(contract_id.clone())
+                .call(|incrementer| incrementer.inc_mine(7));
+            client
+                .call(&ink_e2e::alice(), inc_mine, 0, None)
+                .await
+                .expect("inc_mine failed");
+
+            let migration_code_hash = client
+                .upload("incrementer_migration", &ink_e2e::alice(), None)
+                .await
+                .expect("uploading `incrementer_migration` failed")
+                .code_hash;
+            let v2_code_hash = client
+                .upload("incrementer_v2", &ink_e2e::alice(), None)
+                .await
+                .expect("uploading `incrementer_v2` failed")
+                .code_hash;
+
+            // Only the owner may swap the code.
+            let set_code = build_message::<IncrementerRef>(contract_id.clone())
+                .call(|incrementer| incrementer.set_code(migration_code_hash.into()));
+            let result = client
+                .call_dry_run(&ink_e2e::bob(), &set_code, 0, None)
+                .await
+                .return_value();
+            assert_eq!(result, Err(Error::NotOwner));
+
+            client
+                .call(&ink_e2e::alice(), set_code, 0, None)
+                .await
+                .expect("set_code failed");
+
+            let migrate = build_message::<MigrationRef>(contract_id.clone())
+                .call(|migration| migration.migrate(v2_code_hash.into()));
+            client
+                .call(&ink_e2e::alice(), migrate, 0, None)
+                .await
+                .expect("migrate failed");
+
+            let get = build_message::<IncrementerV2Ref>(contract_id.clone())
+                .call(|incrementer| incrementer.get());
+            let value = client
+                .call_dry_run(&ink_e2e::alice(), &get, 0, None)
+                .await
+                .return_value();
+            assert_eq!(value, 47);
+
+            let get_mine = build_message::<IncrementerV2Ref>(contract_id.clone())
+                .call(|incrementer| incrementer.get_mine());
+            let mine = client
+                .call_dry_run(&ink_e2e::alice(), &get_mine, 0, None)
+                .await
+                .return_value();
+            assert_eq!(mine, 7);
+
+            let inc_count = build_message::<IncrementerV2Ref>(contract_id.clone())
+                .call(|incrementer| incrementer.inc_count());
+            let count = client
+                .call_dry_run(&ink_e2e::alice(), &inc_count, 0, None)
+                .await
+                .return_value();
+            assert_eq!(count, 0);
+
+            Ok(())
         }
     }
 }
@@ -0,0 +1,95 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub use self::migration::{Migration, MigrationRef};
+
+/// Moves an `Incrementer` from the storage layout of `incrementer-mapping.rs`
+/// to the layout of `incrementer-mapping-v2.rs`.
+///
+/// Upgrade a live instance by calling `set_code` with the code hash of this
+/// contract, then calling `migrate` with the code hash of the v2 contract.
+#[ink::contract]
+mod migration {
+    /// The root storage of `incrementer-mapping.rs`.
+    ///
+    /// `Mapping` fields store their entries under their own keys, so only the
+    /// packed fields are part of the root value.
+    #[derive(scale::Encode, scale::Decode)]
+    struct OldIncrementer {
+        value: i32,
+        owner: AccountId,
+        min_value: Option<i32>,
+        max_value: Option<i32>,
+    }
+
+    /// The root storage of `incrementer-mapping-v2.rs`.
+    #[derive(scale::Encode, scale::Decode)]
+    struct NewIncrementer {
+        value: i32,
+        owner: AccountId,
+        min_value: Option<i32>,
+        max_value: Option<i32>,
+        inc_count: u64,
+    }
+
+    /// The migration keeps no state of its own, so it can run on top of any
+    /// existing storage.
+    #[ink(storage)]
+    pub struct Migration {}
+
+    /// Specify the migration error type.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not the incrementer owner.
+        NotOwner,
+        /// Returned if the old incrementer storage is missing or cannot be
+        /// decoded.
+        InvalidStorage,
+        /// Returned if the contract code could not be replaced.
+        SetCodeHashFailed,
+    }
+
+    impl Default for Migration {
+        fn default() -> Self {
+            Self {}
+        }
+    }
+
+    impl Migration {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Rewrites the incrementer storage in the v2 layout and switches the
+        /// contract code to `code_hash`.
+        ///
+        /// This message takes `&self` so that the empty `Migration` storage is
+        /// not written back over the migrated storage.
+        #[ink(message)]
+        pub fn migrate(&self, code_hash: [u8; 32]) -> Result<(), Error> {
+            const ROOT_KEY: u32 = <Migration as ink::storage::traits::StorageKey>::KEY;
+
+            let old = ink::env::get_contract_storage::<_, OldIncrementer>(&ROOT_KEY)
+                .ok()
+                .flatten()
+                .ok_or(Error::InvalidStorage)?;
+            if self.env().caller() != old.owner {
+                return Err(Error::NotOwner);
+            }
+
+            let new = NewIncrementer {
+                value: old.value,
+                owner: old.owner,
+                min_value: old.min_value,
+                max_value: old.max_value,
+                inc_count: 0,
+            };
+            ink::env::set_contract_storage(&ROOT_KEY, &new);
+
+            ink::env::set_code_hash(&code_hash).map_err(|_| Error::SetCodeHashFailed)?;
+
+            Ok(())
+        }
+    }
+}
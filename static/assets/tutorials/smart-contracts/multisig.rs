@@ -0,0 +1,688 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// A wallet that makes calls on behalf of a group of owners.
+///
+/// Any owner can submit a call to another contract. The call is executed only
+/// after `threshold` owners have confirmed it.
+#[ink::contract]
+mod multisig {
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+
+    /// The identifier of a submitted transaction.
+    pub type TransactionId = u32;
+
+    /// A call to another contract that waits for confirmations.
+    ///
+    /// The called message must return `Result<(), E>`, like `Erc20::transfer`.
+    #[derive(Debug, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Transaction {
+        /// The contract to call.
+        pub callee: AccountId,
+        /// The selector of the message to call.
+        pub selector: [u8; 4],
+        /// The SCALE-encoded arguments of the message.
+        pub input: Vec<u8>,
+        /// The value transferred along with the call.
+        pub transferred_value: Balance,
+        /// The gas limit of the call, where 0 means all remaining gas.
+        pub gas_limit: u64,
+        /// Whether the call was executed.
+        pub executed: bool,
+    }
+
+    /// Passes already encoded arguments to the `CallBuilder` unchanged.
+    struct CallInput<'a>(&'a [u8]);
+
+    impl scale::Encode for CallInput<'_> {
+        fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+            dest.write(self.0);
+        }
+    }
+
+    #[ink(storage)]
+    pub struct Multisig {
+        /// The accounts that can submit and confirm transactions.
+        owners: Vec<AccountId>,
+        /// The number of confirmations a transaction needs to be executed.
+        threshold: u32,
+        /// The identifier of the next submitted transaction.
+        next_id: TransactionId,
+        /// The submitted transactions.
+        transactions: Mapping<TransactionId, Transaction>,
+        /// The owners who confirmed each transaction.
+        confirmations: Mapping<(TransactionId, AccountId), ()>,
+        /// The number of confirmations of each transaction.
+        confirmation_counts: Mapping<TransactionId, u32>,
+    }
+
+    #[ink(event)]
+    pub struct Submission {
+        #[ink(topic)]
+        id: TransactionId,
+        #[ink(topic)]
+        owner: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Confirmation {
+        #[ink(topic)]
+        id: TransactionId,
+        #[ink(topic)]
+        owner: AccountId,
+        confirmations: u32,
+    }
+
+    #[ink(event)]
+    pub struct Revocation {
+        #[ink(topic)]
+        id: TransactionId,
+        #[ink(topic)]
+        owner: AccountId,
+        confirmations: u32,
+    }
+
+    #[ink(event)]
+    pub struct Execution {
+        #[ink(topic)]
+        id: TransactionId,
+    }
+
+    /// Specify the multisig error type.
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Error {
+        /// Returned if the caller is not one of the owners.
+        NotOwner,
+        /// Returned if an account appears more than once in the owners.
+        DuplicateOwner,
+        /// Returned if the threshold is zero or larger than the number of owners.
+        InvalidThreshold,
+        /// Returned if no transaction with the given ID exists.
+        TransactionNotFound,
+        /// Returned if the transaction was already executed.
+        AlreadyExecuted,
+        /// Returned if the caller already confirmed the transaction.
+        AlreadyConfirmed,
+        /// Returned if the caller has not confirmed the transaction.
+        NotConfirmed,
+        /// Returned if the transaction has fewer confirmations than the threshold.
+        NotEnoughConfirmations,
+        /// Returned if the called contract failed or reverted.
+        TransactionFailed,
+        /// Returned if no more transaction IDs are available.
+        Overflow,
+    }
+
+    /// Specify the multisig result type.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    impl Multisig {
+        /// Create a new multisig wallet that needs `threshold` of `owners` to
+        /// confirm each transaction.
+        #[ink(constructor)]
+        pub fn new(owners: Vec<AccountId>, threshold: u32) -> Result<Self> {
+            for (index, owner) in owners.iter().enumerate() {
+                if owners[..index].contains(owner) {
+                    return Err(Error::DuplicateOwner);
+                }
+            }
+            if threshold == 0 || threshold as usize > owners.len() {
+                return Err(Error::InvalidThreshold);
+            }
+
+            Ok(Self {
+                owners,
+                threshold,
+                next_id: 0,
+                transactions: Mapping::default(),
+                confirmations: Mapping::default(),
+                confirmation_counts: Mapping::default(),
+            })
+        }
+
+        /// Returns the owners of the wallet.
+        #[ink(message)]
+        pub fn owners(&self) -> Vec<AccountId> {
+            self.owners.clone()
+        }
+
+        /// Returns the number of confirmations a transaction needs.
+        #[ink(message)]
+        pub fn threshold(&self) -> u32 {
+            self.threshold
+        }
+
+        /// Returns the transaction with the given ID, if any.
+        #[ink(message)]
+        pub fn transaction(&self, id: TransactionId) -> Option<Transaction> {
+            self.transactions.get(id)
+        }
+
+        /// Returns the number of confirmations of the transaction.
+        #[ink(message)]
+        pub fn confirmation_count(&self, id: TransactionId) -> u32 {
+            self.confirmation_counts.get(id).unwrap_or_default()
+        }
+
+        /// Returns `true` if `owner` confirmed the transaction.
+        #[ink(message)]
+        pub fn is_confirmed_by(&self, id: TransactionId, owner: AccountId) -> bool {
+            self.confirmations.contains((id, owner))
+        }
+
+        /// Submits a call of the message with `selector` on `callee`, taking
+        /// the SCALE-encoded arguments in `input`.
+        ///
+        /// The submission counts as the caller's confirmation.
+        #[ink(message)]
+        pub fn submit_transaction(
+            &mut self,
+            callee: AccountId,
+            selector: [u8; 4],
+            input: Vec<u8>,
+            transferred_value: Balance,
+            gas_limit: u64,
+        ) -> Result<TransactionId> {
+            let owner = self.ensure_owner()?;
+            let id = self.next_id;
+            self.next_id = id.checked_add(1).ok_or(Error::Overflow)?;
+
+            let transaction = Transaction {
+                callee,
+                selector,
+                input,
+                transferred_value,
+                gas_limit,
+                executed: false,
+            };
+            self.transactions.insert(id, &transaction);
+
+            // The `erc20` dev-dependency of the e2e tests adds a second
+            // `EmitEvent` impl, so the call names the one of this contract.
+            <ink::EnvAccess<Environment> as ink::codegen::EmitEvent<Multisig>>::emit_event(
+                self.env(),
+                Submission { id, owner },
+            );
+
+            self.confirm_transaction(id)?;
+            Ok(id)
+        }
+
+        /// Confirms the transaction on behalf of the caller.
+        #[ink(message)]
+        pub fn confirm_transaction(&mut self, id: TransactionId) -> Result<()> {
+            let owner = self.ensure_owner()?;
+            self.ensure_pending(id)?;
+            if self.is_confirmed_by(id, owner) {
+                return Err(Error::AlreadyConfirmed);
+            }
+
+            let confirmations = self.confirmation_count(id) + 1;
+            self.confirmations.insert((id, owner), &());
+            self.confirmation_counts.insert(id, &confirmations);
+
+            <ink::EnvAccess<Environment> as ink::codegen::EmitEvent<Multisig>>::emit_event(
+                self.env(),
+                Confirmation {
+                    id,
+                    owner,
+                    confirmations,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Withdraws the caller's confirmation of the transaction.
+        #[ink(message)]
+        pub fn revoke_confirmation(&mut self, id: TransactionId) -> Result<()> {
+            let owner = self.ensure_owner()?;
+            self.ensure_pending(id)?;
+            if !self.is_confirmed_by(id, owner) {
+                return Err(Error::NotConfirmed);
+            }
+
+            let confirmations = self.confirmation_count(id) - 1;
+            self.confirmations.remove((id, owner));
+            self.confirmation_counts.insert(id, &confirmations);
+
+            <ink::EnvAccess<Environment> as ink::codegen::EmitEvent<Multisig>>::emit_event(
+                self.env(),
+                Revocation {
+                    id,
+                    owner,
+                    confirmations,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Executes the transaction once it has enough confirmations.
+        ///
+        /// If the called message fails or returns an `Err`, the transaction
+        /// stays pending and can be executed again.
+        #[ink(message)]
+        pub fn execute_transaction(&mut self, id: TransactionId) -> Result<()> {
+            self.ensure_owner()?;
+            let mut transaction = self.ensure_pending(id)?;
+            if self.confirmation_count(id) < self.threshold {
+                return Err(Error::NotEnoughConfirmations);
+            }
+
+            // Mark the transaction as executed before making the call.
+            transaction.executed = true;
+            self.transactions.insert(id, &transaction);
+
+            let result = build_call::<Environment>()
+                .call(transaction.callee)
+                .gas_limit(transaction.gas_limit)
+                .transferred_value(transaction.transferred_value)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(transaction.selector))
+                        .push_arg(CallInput(&transaction.input)),
+                )
+                // The error type of the called message is not known here. Its
+                // `Err` payload is discarded, and any outcome other than
+                // `Ok(())` maps to `TransactionFailed` below.
+                .returns::<core::result::Result<(), ()>>()
+                .try_invoke();
+            if !matches!(result, Ok(Ok(Ok(())))) {
+                return Err(Error::TransactionFailed);
+            }
+
+            <ink::EnvAccess<Environment> as ink::codegen::EmitEvent<Multisig>>::emit_event(
+                self.env(),
+                Execution { id },
+            );
+
+            Ok(())
+        }
+
+        /// Returns the caller if it is one of the owners.
+        fn ensure_owner(&self) -> Result<AccountId> {
+            let caller = self.env().caller();
+            if !self.owners.contains(&caller) {
+                return Err(Error::NotOwner);
+            }
+            Ok(caller)
+        }
+
+        /// Returns the transaction if it exists and was not executed yet.
+        fn ensure_pending(&self, id: TransactionId) -> Result<Transaction> {
+            let transaction = self.transaction(id).ok_or(Error::TransactionNotFound)?;
+            if transaction.executed {
+                return Err(Error::AlreadyExecuted);
+            }
+            Ok(transaction)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        type Event = <Multisig as ink::reflect::ContractEventBase>::Type;
+
+        fn default_accounts() -> ink::env::test::DefaultAccounts<Environment> {
+            ink::env::test::default_accounts::<Environment>()
+        }
+
+        fn alice() -> AccountId {
+            default_accounts().alice
+        }
+
+        fn bob() -> AccountId {
+            default_accounts().bob
+        }
+
+        fn charlie() -> AccountId {
+            default_accounts().charlie
+        }
+
+        fn set_caller(caller: AccountId) {
+            ink::env::test::set_caller::<Environment>(caller);
+        }
+
+        fn recorded_events() -> Vec<Event> {
+            ink::env::test::recorded_events()
+                .map(|event| {
+                    <Event as scale::Decode>::decode(&mut &event.data[..])
+                        .expect("encountered invalid contract event data buffer")
+                })
+                .collect()
+        }
+
+        fn assert_submission_event(
+            event: &Event,
+            expected_id: TransactionId,
+            expected_owner: AccountId,
+        ) {
+            if let Event::Submission(Submission { id, owner }) = event {
+                assert_eq!(*id, expected_id, "encountered invalid Submission.id");
+                assert_eq!(
+                    *owner, expected_owner,
+                    "encountered invalid Submission.owner"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected a Submission event")
+            }
+        }
+
+        fn assert_confirmation_event(
+            event: &Event,
+            expected_id: TransactionId,
+            expected_owner: AccountId,
+            expected_confirmations: u32,
+        ) {
+            if let Event::Confirmation(Confirmation {
+                id,
+                owner,
+                confirmations,
+            }) = event
+            {
+                assert_eq!(*id, expected_id, "encountered invalid Confirmation.id");
+                assert_eq!(
+                    *owner, expected_owner,
+                    "encountered invalid Confirmation.owner"
+                );
+                assert_eq!(
+                    *confirmations, expected_confirmations,
+                    "encountered invalid Confirmation.confirmations"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected a Confirmation event")
+            }
+        }
+
+        fn assert_revocation_event(
+            event: &Event,
+            expected_id: TransactionId,
+            expected_owner: AccountId,
+            expected_confirmations: u32,
+        ) {
+            if let Event::Revocation(Revocation {
+                id,
+                owner,
+                confirmations,
+            }) = event
+            {
+                assert_eq!(*id, expected_id, "encountered invalid Revocation.id");
+                assert_eq!(
+                    *owner, expected_owner,
+                    "encountered invalid Revocation.owner"
+                );
+                assert_eq!(
+                    *confirmations, expected_confirmations,
+                    "encountered invalid Revocation.confirmations"
+                );
+            } else {
+                panic!("encountered unexpected event kind: expected a Revocation event")
+            }
+        }
+
+        /// Creates a wallet owned by `alice`, `bob` and `charlie` that needs
+        /// two confirmations.
+        fn two_of_three() -> Multisig {
+            Multisig::new(Vec::from([alice(), bob(), charlie()]), 2).expect("valid owners")
+        }
+
+        /// Submits a call of `transfer(django, 100)` on `eve` as `alice`.
+        fn submit(contract: &mut Multisig) -> Result<TransactionId> {
+            let input = scale::Encode::encode(&(default_accounts().django, 100 as Balance));
+            contract.submit_transaction(
+                default_accounts().eve,
+                ink::selector_bytes!("transfer"),
+                input,
+                0,
+                0,
+            )
+        }
+
+        #[ink::test]
+        fn new_works() {
+            let contract = two_of_three();
+            assert_eq!(contract.owners(), Vec::from([alice(), bob(), charlie()]));
+            assert_eq!(contract.threshold(), 2);
+            assert_eq!(contract.transaction(0), None);
+        }
+
+        #[ink::test]
+        fn new_rejects_invalid_owners() {
+            assert_eq!(
+                Multisig::new(Vec::from([alice(), bob(), alice()]), 2).err(),
+                Some(Error::DuplicateOwner)
+            );
+            assert_eq!(
+                Multisig::new(Vec::from([alice(), bob()]), 0).err(),
+                Some(Error::InvalidThreshold)
+            );
+            assert_eq!(
+                Multisig::new(Vec::from([alice(), bob()]), 3).err(),
+                Some(Error::InvalidThreshold)
+            );
+        }
+
+        #[ink::test]
+        fn submit_transaction_works() {
+            let mut contract = two_of_three();
+            assert_eq!(submit(&mut contract), Ok(0));
+            assert_eq!(submit(&mut contract), Ok(1));
+
+            let transaction = contract.transaction(0).expect("transaction was submitted");
+            assert_eq!(transaction.callee, default_accounts().eve);
+            assert_eq!(transaction.selector, ink::selector_bytes!("transfer"));
+            assert!(!transaction.executed);
+
+            // Submitting counts as a confirmation.
+            assert_eq!(contract.confirmation_count(0), 1);
+            assert!(contract.is_confirmed_by(0, alice()));
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 4);
+            assert_submission_event(&emitted_events[0], 0, alice());
+            assert_confirmation_event(&emitted_events[1], 0, alice(), 1);
+            assert_submission_event(&emitted_events[2], 1, alice());
+            assert_confirmation_event(&emitted_events[3], 1, alice(), 1);
+        }
+
+        #[ink::test]
+        fn only_owners_can_submit_and_confirm() {
+            let mut contract = two_of_three();
+            assert_eq!(submit(&mut contract), Ok(0));
+
+            set_caller(default_accounts().django);
+            assert_eq!(submit(&mut contract), Err(Error::NotOwner));
+            assert_eq!(contract.confirm_transaction(0), Err(Error::NotOwner));
+            assert_eq!(contract.revoke_confirmation(0), Err(Error::NotOwner));
+            assert_eq!(contract.execute_transaction(0), Err(Error::NotOwner));
+            assert_eq!(contract.confirmation_count(0), 1);
+            assert_eq!(recorded_events().len(), 2);
+        }
+
+        #[ink::test]
+        fn confirm_transaction_works() {
+            let mut contract = two_of_three();
+            assert_eq!(submit(&mut contract), Ok(0));
+            assert_eq!(
+                contract.confirm_transaction(0),
+                Err(Error::AlreadyConfirmed)
+            );
+            assert_eq!(
+                contract.confirm_transaction(1),
+                Err(Error::TransactionNotFound)
+            );
+
+            set_caller(bob());
+            assert_eq!(contract.confirm_transaction(0), Ok(()));
+            assert_eq!(contract.confirmation_count(0), 2);
+            assert!(contract.is_confirmed_by(0, bob()));
+            assert!(!contract.is_confirmed_by(0, charlie()));
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 3);
+            assert_confirmation_event(&emitted_events[2], 0, bob(), 2);
+        }
+
+        #[ink::test]
+        fn revoke_confirmation_works() {
+            let mut contract = two_of_three();
+            assert_eq!(submit(&mut contract), Ok(0));
+            set_caller(bob());
+            assert_eq!(contract.revoke_confirmation(0), Err(Error::NotConfirmed));
+            assert_eq!(contract.confirm_transaction(0), Ok(()));
+            assert_eq!(contract.revoke_confirmation(0), Ok(()));
+            assert_eq!(contract.confirmation_count(0), 1);
+            assert!(!contract.is_confirmed_by(0, bob()));
+
+            // Without bob's confirmation the threshold is no longer met.
+            assert_eq!(
+                contract.execute_transaction(0),
+                Err(Error::NotEnoughConfirmations)
+            );
+
+            let emitted_events = recorded_events();
+            assert_eq!(emitted_events.len(), 4);
+            assert_confirmation_event(&emitted_events[2], 0, bob(), 2);
+            assert_revocation_event(&emitted_events[3], 0, bob(), 1);
+        }
+
+        #[ink::test]
+        fn execute_requires_threshold() {
+            let mut contract = two_of_three();
+            assert_eq!(submit(&mut contract), Ok(0));
+            assert_eq!(
+                contract.execute_transaction(0),
+                Err(Error::NotEnoughConfirmations)
+            );
+            assert_eq!(
+                contract.execute_transaction(1),
+                Err(Error::TransactionNotFound)
+            );
+            assert!(
+                !contract
+                    .transaction(0)
+                    .expect("transaction was submitted")
+                    .executed
+            );
+        }
+    }
+
+    /// End-to-end tests in which the wallet moves tokens of the `Erc20` in
+    /// `erc20-final.rs`, which must be a dev-dependency for them to build.
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use erc20::Erc20Ref;
+        use ink_e2e::build_message;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test(additional_contracts = "../erc20/Cargo.toml")]
+        async fn e2e_moves_erc20_after_two_confirmations(
+            mut client: ink_e2e::Client<C, E>,
+        ) -> E2EResult<()> {
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let charlie = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+            let dave = ink_e2e::account_id(ink_e2e::AccountKeyring::Dave);
+
+            let constructor = Erc20Ref::new(1_000);
+            let erc20_id = client
+                .instantiate("erc20", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate `erc20` failed")
+                .account_id;
+
+            let constructor = MultisigRef::new(Vec::from([alice, bob, charlie]), 2);
+            let multisig_id = client
+                .instantiate("multisig", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("instantiate `multisig` failed")
+                .account_id;
+
+            // Fund the wallet with tokens.
+            let transfer = build_message::<Erc20Ref>(erc20_id.clone())
+                .call(|erc20| erc20.transfer(multisig_id.clone(), 500));
+            client
+                .call(&ink_e2e::alice(), transfer, 0, None)
+                .await
+                .expect("funding the multisig failed");
+
+            // Alice submits a transfer of 100 tokens to dave.
+            let input = scale::Encode::encode(&(dave.clone(), 100 as Balance));
+            let submit = build_message::<MultisigRef>(multisig_id.clone()).call(|multisig| {
+                multisig.submit_transaction(
+                    erc20_id.clone(),
+                    ink::selector_bytes!("transfer"),
+                    input.clone(),
+                    0,
+                    0,
+                )
+            });
+            let id = client
+                .call(&ink_e2e::alice(), submit, 0, None)
+                .await
+                .expect("submit failed")
+                .return_value()
+                .expect("submit returned an error");
+
+            // One confirmation is not enough.
+            let execute = build_message::<MultisigRef>(multisig_id.clone())
+                .call(|multisig| multisig.execute_transaction(id));
+            let result = client
+                .call_dry_run(&ink_e2e::charlie(), &execute, 0, None)
+                .await
+                .return_value();
+            assert_eq!(result, Err(Error::NotEnoughConfirmations));
+
+            // Bob's confirmation reaches the threshold, so charlie can execute.
+            let confirm = build_message::<MultisigRef>(multisig_id.clone())
+                .call(|multisig| multisig.confirm_transaction(id));
+            client
+                .call(&ink_e2e::bob(), confirm, 0, None)
+                .await
+                .expect("confirm failed");
+
+            let execute = build_message::<MultisigRef>(multisig_id.clone())
+                .call(|multisig| multisig.execute_transaction(id));
+            client
+                .call(&ink_e2e::charlie(), execute, 0, None)
+                .await
+                .expect("execute failed");
+
+            let balance_of =
+                build_message::<Erc20Ref>(erc20_id.clone()).call(|erc20| erc20.balance_of(dave));
+            let balance = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, 100);
+
+            let balance_of = build_message::<Erc20Ref>(erc20_id.clone())
+                .call(|erc20| erc20.balance_of(multisig_id));
+            let balance = client
+                .call_dry_run(&ink_e2e::alice(), &balance_of, 0, None)
+                .await
+                .return_value();
+            assert_eq!(balance, 400);
+
+            // An executed transaction cannot be executed again.
+            let execute = build_message::<MultisigRef>(multisig_id.clone())
+                .call(|multisig| multisig.execute_transaction(id));
+            let result = client
+                .call_dry_run(&ink_e2e::alice(), &execute, 0, None)
+                .await
+                .return_value();
+            assert_eq!(result, Err(Error::AlreadyExecuted));
+
+            Ok(())
+        }
+    }
+}
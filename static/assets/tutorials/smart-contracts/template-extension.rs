@@ -0,0 +1,154 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ink::env::Environment;
+
+/// The runtime functions exposed by the chain extension in
+/// `runtime-upgrade/lib-spec-version-103.rs`.
+///
+/// Each `extension` ID must match a function ID handled by the runtime's
+/// `TemplateExtension`.
+#[ink::chain_extension]
+pub trait TemplateExtension {
+    type ErrorCode = TemplateExtensionError;
+
+    /// Returns `RandomnessCollectiveFlip::random` for `subject`.
+    #[ink(extension = 1101)]
+    fn fetch_random(subject: [u8; 32]) -> [u8; 32];
+
+    /// Returns the value stored by `TemplateModule`, if any.
+    #[ink(extension = 1102)]
+    fn something() -> Option<u32>;
+}
+
+/// Specify the chain extension error type.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum TemplateExtensionError {
+    /// Returned if the runtime reports a status code other than 0.
+    Failed,
+}
+
+impl ink::env::chain_extension::FromStatusCode for TemplateExtensionError {
+    fn from_status_code(status_code: u32) -> Result<(), Self> {
+        match status_code {
+            0 => Ok(()),
+            _ => Err(Self::Failed),
+        }
+    }
+}
+
+/// The default environment extended with `TemplateExtension`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum CustomEnvironment {}
+
+impl Environment for CustomEnvironment {
+    const MAX_EVENT_TOPICS: usize = <ink::env::DefaultEnvironment as Environment>::MAX_EVENT_TOPICS;
+
+    type AccountId = <ink::env::DefaultEnvironment as Environment>::AccountId;
+    type Balance = <ink::env::DefaultEnvironment as Environment>::Balance;
+    type Hash = <ink::env::DefaultEnvironment as Environment>::Hash;
+    type BlockNumber = <ink::env::DefaultEnvironment as Environment>::BlockNumber;
+    type Timestamp = <ink::env::DefaultEnvironment as Environment>::Timestamp;
+
+    type ChainExtension = TemplateExtension;
+}
+
+/// Reads randomness and `TemplateModule` storage through `TemplateExtension`.
+#[ink::contract(env = crate::CustomEnvironment)]
+mod template_reader {
+    use super::TemplateExtensionError;
+
+    #[ink(storage)]
+    pub struct TemplateReader {
+        /// The last random value fetched from the runtime.
+        random: [u8; 32],
+    }
+
+    #[ink(event)]
+    pub struct RandomUpdated {
+        #[ink(topic)]
+        random: [u8; 32],
+    }
+
+    impl TemplateReader {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self { random: [0; 32] }
+        }
+
+        /// Fetches a new random value for `subject` and stores it.
+        #[ink(message)]
+        pub fn update_random(&mut self, subject: [u8; 32]) -> Result<(), TemplateExtensionError> {
+            let random = self.env().extension().fetch_random(subject)?;
+            self.random = random;
+            self.env().emit_event(RandomUpdated { random });
+            Ok(())
+        }
+
+        /// Returns the last random value fetched from the runtime.
+        #[ink(message)]
+        pub fn random(&self) -> [u8; 32] {
+            self.random
+        }
+
+        /// Returns the value stored by `TemplateModule`, if any.
+        #[ink(message)]
+        pub fn something(&self) -> Result<Option<u32>, TemplateExtensionError> {
+            self.env().extension().something()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        /// Stands in for the runtime's `fetch_random`.
+        struct MockedFetchRandom;
+
+        impl ink::env::test::ChainExtension for MockedFetchRandom {
+            fn func_id(&self) -> u32 {
+                1101
+            }
+
+            fn call(&mut self, _input: &[u8], output: &mut Vec<u8>) -> u32 {
+                scale::Encode::encode_to(&[0x01u8; 32], output);
+                0
+            }
+        }
+
+        /// Stands in for the runtime's `something`.
+        struct MockedSomething(Option<u32>);
+
+        impl ink::env::test::ChainExtension for MockedSomething {
+            fn func_id(&self) -> u32 {
+                1102
+            }
+
+            fn call(&mut self, _input: &[u8], output: &mut Vec<u8>) -> u32 {
+                scale::Encode::encode_to(&self.0, output);
+                0
+            }
+        }
+
+        #[ink::test]
+        fn update_random_works() {
+            ink::env::test::register_chain_extension(MockedFetchRandom);
+            let mut contract = TemplateReader::new();
+            assert_eq!(contract.random(), [0; 32]);
+            assert_eq!(contract.update_random([0x02; 32]), Ok(()));
+            assert_eq!(contract.random(), [0x01; 32]);
+            assert_eq!(ink::env::test::recorded_events().count(), 1);
+        }
+
+        #[ink::test]
+        fn something_works() {
+            ink::env::test::register_chain_extension(MockedSomething(Some(42)));
+            let contract = TemplateReader::new();
+            assert_eq!(contract.something(), Ok(Some(42)));
+
+            ink::env::test::register_chain_extension(MockedSomething(None));
+            assert_eq!(contract.something(), Ok(None));
+        }
+    }
+}